@@ -0,0 +1,25 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBEDDED_TABLES").is_none() {
+        return;
+    }
+
+    let missing: Vec<String> = (1..=4)
+        .map(|id| format!("tabs/mt_table_{}", id))
+        .inspect(|file| println!("cargo:rerun-if-changed={}", file))
+        .filter(|file| !Path::new(file).exists())
+        .collect();
+
+    if !missing.is_empty() {
+        eprintln!(
+            "error: the embedded-tables feature needs {}; generate them first with\n  \
+            cargo run --release -- --tables-dir=tabs --tab=2,3,4",
+            missing.join(", ")
+        );
+        process::exit(1);
+    }
+}
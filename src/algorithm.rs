@@ -0,0 +1,251 @@
+use super::*;
+use serde::{Serialize, Serializer};
+use std::ops::{Add, Deref, DerefMut};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Htm,
+    Qtm,
+    Stm,
+    Etm,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Algorithm(pub Vec<Move>);
+
+impl Algorithm {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn inv(&self) -> Algorithm {
+        self.iter().rev().map(|mv| mv.inv()).collect()
+    }
+
+    pub fn mirror(&self, axis: Axis) -> Algorithm {
+        self.iter()
+            .map(|mv| {
+                let Move(layer, ..) = *mv;
+
+                match layer {
+                    Outer(face) | Wide(face) if face.axis() == axis => {
+                        let face = face.opposite();
+                        let layer = if let Outer(_) = layer {
+                            Outer(face)
+                        } else {
+                            Wide(face)
+                        };
+
+                        Move(layer, mv.1, mv.2).inv()
+                    }
+                    Inner(_) | Whole(_) if layer.axis() == axis => *mv,
+                    _ => mv.inv(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn concat(&self, other: &Algorithm) -> Algorithm {
+        self.iter().chain(other.iter()).cloned().collect()
+    }
+
+    pub fn repeat(&self, nb: usize) -> Algorithm {
+        Self(self.0.repeat(nb))
+    }
+
+    pub fn simplify(&self) -> Algorithm {
+        let mut result = Algorithm::new();
+
+        for mv in self {
+            let Move(layer, ..) = *mv;
+            let mut same = None;
+
+            for (i, prev) in result.iter().enumerate().rev() {
+                if prev.0 == layer {
+                    same = Some(i);
+                    break;
+                } else if prev.0.axis() != layer.axis() {
+                    break;
+                }
+            }
+            match same {
+                Some(i) => match Move::from_quarters(layer, result[i].quarters() + mv.quarters()) {
+                    Some(merged) => result[i] = merged,
+                    None => {
+                        result.remove(i);
+                    }
+                },
+                None => result.push(*mv),
+            }
+        }
+        result
+    }
+
+    pub fn notation(&self) -> String {
+        self.iter()
+            .map(|mv| mv.notation())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn len_in(&self, metric: Metric) -> usize {
+        self.iter()
+            .map(|Move(layer, _, typ)| {
+                let quarters = if let Dual = typ { 2 } else { 1 };
+
+                match (layer, metric) {
+                    (_, Etm) => 1,
+                    (Whole(_), _) => 0,
+                    (_, Stm) => 1,
+                    (Inner(_), Htm) => 2,
+                    (Inner(_), Qtm) => 2 * quarters,
+                    (_, Htm) => 1,
+                    (_, Qtm) => quarters,
+                }
+            })
+            .sum()
+    }
+}
+
+impl Deref for Algorithm {
+    type Target = Vec<Move>;
+
+    fn deref(&self) -> &Vec<Move> {
+        &self.0
+    }
+}
+
+impl DerefMut for Algorithm {
+    fn deref_mut(&mut self) -> &mut Vec<Move> {
+        &mut self.0
+    }
+}
+
+impl From<Vec<Move>> for Algorithm {
+    fn from(movs: Vec<Move>) -> Self {
+        Self(movs)
+    }
+}
+
+impl FromIterator<Move> for Algorithm {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Algorithm {
+    type Item = Move;
+    type IntoIter = std::vec::IntoIter<Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Algorithm {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Add for Algorithm {
+    type Output = Algorithm;
+
+    fn add(mut self, other: Algorithm) -> Algorithm {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.notation())
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Cube::alg_parser(s).map(Self)
+    }
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, mv) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", mv)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alg(s: &str) -> Algorithm {
+        s.parse().unwrap()
+    }
+
+    fn simplify(s: &str) -> String {
+        alg(s).simplify().notation()
+    }
+
+    #[test]
+    fn merges_across_same_axis() {
+        assert_eq!(simplify("R L R"), "R2 L");
+        assert_eq!(simplify("R L R'"), "L");
+        assert_eq!(simplify("U D' U2"), "U' D'");
+        assert_eq!(simplify("M R M"), "M2 R");
+    }
+
+    #[test]
+    fn stops_at_other_axis() {
+        assert_eq!(simplify("R U R"), "R U R");
+        assert_eq!(simplify("R U U' R"), "R2");
+    }
+
+    #[test]
+    fn cancels() {
+        assert_eq!(simplify("R R'"), "");
+        assert_eq!(simplify("R2 R2"), "");
+        assert_eq!(simplify("R U F F' U' R'"), "");
+        assert_eq!(simplify("(R U)3 (U' R')3"), "");
+    }
+
+    #[test]
+    fn inv() {
+        assert_eq!(alg("R U2 F' x").inv().notation(), "x' F U2 R'");
+        assert_eq!(alg("R U2 F' x").inv().inv(), alg("R U2 F' x"));
+    }
+
+    #[test]
+    fn mirror() {
+        assert_eq!(alg("R U R' U'").mirror(X).notation(), "L' U' L U");
+        assert_eq!(alg("Rw").mirror(X).notation(), "Lw'");
+        assert_eq!(alg("x").mirror(Y).notation(), "x'");
+        assert_eq!(alg("M x").mirror(X).notation(), "M x");
+        for axis in Axis::AXIS_SET {
+            let movs = alg("R Uw2 F' M E S' x y2 z'");
+
+            assert_eq!(movs.mirror(axis).mirror(axis), movs);
+        }
+    }
+
+    #[test]
+    fn len_in() {
+        let movs = alg("Rw U M' x y2 S E'");
+
+        assert_eq!(movs.len_in(Htm), 8);
+        assert_eq!(movs.len_in(Stm), 5);
+        assert_eq!(movs.len_in(Etm), 7);
+        assert_eq!(alg("R2 U M2").len_in(Qtm), 7);
+    }
+}
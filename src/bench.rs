@@ -0,0 +1,162 @@
+use super::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub min: usize,
+    pub mean: f64,
+    pub max: usize,
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+impl Stats {
+    pub fn new(lens: &[usize]) -> Self {
+        let mut histogram = BTreeMap::new();
+
+        for len in lens {
+            *histogram.entry(*len).or_insert(0) += 1;
+        }
+        Self {
+            min: lens.iter().cloned().min().unwrap_or(0),
+            mean: lens.iter().sum::<usize>() as f64 / lens.len().max(1) as f64,
+            max: lens.iter().cloned().max().unwrap_or(0),
+            histogram,
+        }
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let top = self.histogram.values().cloned().max().unwrap_or(1);
+
+        writeln!(
+            f,
+            "min {} / mean {:.2} / max {}",
+            self.min, self.mean, self.max
+        )?;
+        for (len, nb) in &self.histogram {
+            writeln!(
+                f,
+                "{:>4} | {:<40} {}",
+                len,
+                "#".repeat((nb * 40).div_ceil(top)),
+                nb
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Percentiles {
+    pub fn new(times: &[Duration]) -> Self {
+        let mut ms: Vec<f64> = times.iter().map(|t| t.as_secs_f64() * 1000.).collect();
+
+        ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let rank = |p: usize| {
+            ms.get((ms.len() * p).div_ceil(100).max(1) - 1)
+                .cloned()
+                .unwrap_or(0.)
+        };
+
+        Self {
+            p50: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: rank(100),
+        }
+    }
+}
+
+impl std::fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "p50 {:.3}ms / p90 {:.3}ms / p99 {:.3}ms / max {:.3}ms",
+            self.p50, self.p90, self.p99, self.max
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub count: usize,
+    pub phases: Vec<Stats>,
+    pub total: Stats,
+    pub phase_times: Vec<Percentiles>,
+    pub total_time: Percentiles,
+}
+
+impl BenchReport {
+    pub fn new(sols: &[Solution]) -> Self {
+        let phase_nb = sols.first().map_or(0, |s| s.phases.len());
+
+        Self {
+            count: sols.len(),
+            phases: (0..phase_nb)
+                .map(|i| {
+                    Stats::new(
+                        &sols
+                            .iter()
+                            .map(|s| s.phases[i].movs.len_in(Htm))
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect(),
+            total: Stats::new(&sols.iter().map(|s| s.len()).collect::<Vec<_>>()),
+            phase_times: (0..phase_nb)
+                .map(|i| {
+                    Percentiles::new(&sols.iter().map(|s| s.phases[i].time).collect::<Vec<_>>())
+                })
+                .collect(),
+            total_time: Percentiles::new(&sols.iter().map(|s| s.time()).collect::<Vec<_>>()),
+        }
+    }
+
+    pub fn run(
+        solver: &Solver,
+        count: usize,
+        mov_nb: usize,
+        group: usize,
+    ) -> Result<Self, SolveError> {
+        let cubes: Vec<Cube> = (0..count).map(|_| Cube::from_rand(mov_nb, group)).collect();
+        let sols = solver
+            .solve_batch(&cubes)
+            .into_iter()
+            .collect::<Result<Vec<Solution>, _>>()?;
+
+        Ok(Self::new(&sols))
+    }
+}
+
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{} {}\n", "CUBES:".bright_green(), self.count)?;
+        for (i, (stats, times)) in self.phases.iter().zip(&self.phase_times).enumerate() {
+            writeln!(
+                f,
+                "{} {}time {}\n",
+                format!("PHASE {}:", i + 1).bright_green(),
+                stats,
+                times
+            )?;
+        }
+        write!(
+            f,
+            "{} {}time {}",
+            "TOTAL:".bright_green(),
+            self.total,
+            self.total_time
+        )
+    }
+}
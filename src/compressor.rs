@@ -1,4 +1,4 @@
-use std::fs;
+use super::*;
 
 pub struct Compressor {
     buff: u128,
@@ -15,16 +15,19 @@ impl Compressor {
         }
     }
 
-    pub fn push<T>(&mut self, data: T, size: usize)
+    pub fn push<T>(&mut self, data: T, size: usize) -> Result<(), TableError>
     where
         u128: From<T>,
     {
-        if self.len + size > 128 {
-            panic!("compressor overflow");
+        let data = u128::from(data);
+
+        if self.len + size > 128 || (size < 128 && data >> size != 0) {
+            return Err(Overflow);
         }
-        self.buff |= u128::from(data) << (128 - (self.len + size));
+        self.buff |= data << (128 - (self.len + size));
         self.len += size;
         self.flush(false);
+        Ok(())
     }
 
     fn flush_byte(&mut self) {
@@ -39,52 +42,111 @@ impl Compressor {
         }
     }
 
-    pub fn save(&mut self, file: &str) {
+    pub fn bytes(&mut self) -> Vec<u8> {
         self.flush(true);
-        fs::write(file, &self.bin.iter().rev().cloned().collect::<Vec<u8>>()).unwrap();
+        self.bin.iter().rev().cloned().collect()
     }
 }
 
-pub struct Decompressor {
+pub struct Decompressor<'a> {
     buff: u128,
     len: usize,
-    bin: Vec<u8>,
+    bin: &'a [u8],
 }
 
-impl Decompressor {
-    pub fn new(file: &str) -> Decompressor {
+impl<'a> Decompressor<'a> {
+    pub fn from_bytes(bin: &'a [u8]) -> Decompressor<'a> {
         Decompressor {
             buff: 0,
             len: 0,
-            bin: fs::read(file).unwrap(),
+            bin,
         }
     }
 
-    pub fn pop<T>(&mut self, size: usize) -> Option<T>
+    pub fn pop<T>(&mut self, size: usize) -> Result<Option<T>, TableError>
     where
         T: TryFrom<u128>,
-        <T as TryFrom<u128>>::Error: std::fmt::Debug,
     {
+        if size > 120 {
+            return Err(Overflow);
+        }
         if size > (self.len + self.bin.len() * 8) {
-            return None;
+            return Ok(None);
         }
-        self.fetch_until(size);
+        self.fetch_until(size)?;
         let result =
             T::try_from((self.buff & ((!(!0 << size)) << (self.len - size))) >> (self.len - size))
-                .unwrap();
+                .map_err(|_| Overflow)?;
         self.len -= size;
-        Some(result)
+        Ok(Some(result))
     }
 
-    fn fetch_until(&mut self, size: usize) {
+    fn fetch_until(&mut self, size: usize) -> Result<(), TableError> {
         while self.len < size {
-            self.fetch_byte();
+            self.fetch_byte()?;
         }
+        Ok(())
     }
 
-    fn fetch_byte(&mut self) {
+    fn fetch_byte(&mut self) -> Result<(), TableError> {
         self.buff <<= 8;
-        self.buff |= self.bin.pop().unwrap() as u128;
+        let (byte, rest) = self.bin.split_last().ok_or(TruncatedRecord)?;
+
+        self.buff |= *byte as u128;
+        self.bin = rest;
         self.len += 8;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut compressor = Compressor::new();
+
+        compressor.push(0xabcu16, 12).unwrap();
+        compressor.push(5u8, 4).unwrap();
+        compressor.push(0x1fu8, 5).unwrap();
+
+        let bin = compressor.bytes();
+        let mut decompressor = Decompressor::from_bytes(&bin);
+
+        assert_eq!(decompressor.pop(12), Ok(Some(0xabcu16)));
+        assert_eq!(decompressor.pop(4), Ok(Some(5u8)));
+        assert_eq!(decompressor.pop(5), Ok(Some(0x1fu8)));
+    }
+
+    #[test]
+    fn push_overflow() {
+        assert_eq!(Compressor::new().push(16u8, 4), Err(Overflow));
+        assert_eq!(Compressor::new().push(15u8, 4), Ok(()));
+    }
+
+    #[test]
+    fn pop_overflow() {
+        let bin = [0xff; 16];
+
+        assert_eq!(
+            Decompressor::from_bytes(&bin).pop::<u128>(121),
+            Err(Overflow)
+        );
+        assert_eq!(Decompressor::from_bytes(&bin).pop::<u8>(9), Err(Overflow));
+    }
+
+    #[test]
+    fn pop_past_end() {
+        let mut compressor = Compressor::new();
+
+        compressor.push(3u8, 4).unwrap();
+
+        let bin = compressor.bytes();
+        let mut decompressor = Decompressor::from_bytes(&bin);
+
+        assert_eq!(decompressor.pop(4), Ok(Some(3u8)));
+        assert_eq!(decompressor.pop::<u8>(5), Ok(None));
+        assert_eq!(Decompressor::from_bytes(&[]).pop::<u8>(1), Ok(None));
     }
 }
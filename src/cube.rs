@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SubCube {
     Core,
     Center(Face, MyColor),
@@ -8,41 +8,75 @@ pub enum SubCube {
     Corner([Face; 3], [MyColor; 3]),
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CubeError {
+    FaceletCount(usize),
+    UnknownFacelet(char),
+    UnknownPiece(String),
+    DuplicatedPiece(String),
+    MisplacedPiece(Id),
+    MisplacedCenters,
+    TwistedCorner(usize),
+    FlippedEdge,
+    OddParity,
+}
+
+impl std::fmt::Display for CubeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FaceletCount(nb) => write!(f, "{} facelets given instead of 54", nb),
+            UnknownFacelet(c) => write!(f, "Facelet \"{}\" is not recognized", c),
+            UnknownPiece(p) => write!(f, "No piece matches facelets \"{}\"", p),
+            DuplicatedPiece(p) => write!(f, "Piece \"{}\" appears more than once", p),
+            MisplacedPiece(pos) => write!(f, "Piece at position {} does not fit its slot", pos),
+            MisplacedCenters => write!(f, "Centers do not match any orientation of the cube"),
+            TwistedCorner(sum) => write!(f, "A corner is twisted (twist sum is {} mod 3)", sum),
+            FlippedEdge => write!(f, "An edge is flipped (flip sum is odd)"),
+            OddParity => write!(f, "Corner and edge permutations have different parities"),
+        }
+    }
+}
+
+impl std::error::Error for CubeError {}
+
+#[derive(Debug, Clone)]
 pub struct Cube {
     pub ids: Vec<Id>,
     pub subs: [SubCube; 27],
-    pub movs: Vec<Move>,
+    pub movs: Algorithm,
+    pub undone: Algorithm,
+    pub scheme: Scheme,
+    pub palette: Palette,
 }
 
 impl std::fmt::Display for Cube {
     fn fmt(&self, fm: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut result = String::with_capacity(0x100);
+        write!(fm, "{}", self.net(&[]))
+    }
+}
 
-        for line in Self::FACE_MAP[Up as usize].chunks(3) {
-            result.push_str(&format!("         {}\n", self.row_2_str(line, Up, false)));
-        }
-        result.push_str("\n");
-        for (l, (f, (r, b))) in Self::FACE_MAP[Left as usize].chunks(3).zip(
-            Self::FACE_MAP[Front as usize].chunks(3).zip(
-                Self::FACE_MAP[Right as usize]
-                    .chunks(3)
-                    .zip(Self::FACE_MAP[Back as usize].chunks(3)),
-            ),
-        ) {
-            result.push_str(&format!(
-                " {}  {}  {}  {}\n",
-                self.row_2_str(l, Left, false),
-                self.row_2_str(f, Front, false),
-                self.row_2_str(r, Right, true),
-                self.row_2_str(b, Back, true),
-            ));
-        }
-        result.push_str("\n");
-        for line in Self::FACE_MAP[Down as usize].chunks(3).rev() {
-            result.push_str(&format!("         {}\n", self.row_2_str(line, Down, false),));
+impl PartialEq for Cube {
+    fn eq(&self, other: &Self) -> bool {
+        self.ids == other.ids && self.subs == other.subs
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::str::FromStr for Cube {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut result = Cube::new();
+
+        for mv in Self::alg_parser(s)? {
+            result.rotate(mv, true);
         }
-        write!(fm, "{}", result)
+        Ok(result)
     }
 }
 
@@ -62,28 +96,92 @@ impl Cube {
         [2, 5, 8, 11, 14, 17, 20, 23, 26],
     ];
 
+    pub const SLICE_MAP: [[Id; 9]; 3] = [
+        [1, 4, 7, 10, 13, 16, 19, 22, 25],
+        [9, 10, 11, 12, 13, 14, 15, 16, 17],
+        [3, 4, 5, 12, 13, 14, 21, 22, 23],
+    ];
+
+    pub const FACELET_ORDER: [Face; 6] = [Up, Right, Front, Down, Left, Back];
+
+    const CORN_FACELETS: [[usize; 3]; 8] = [
+        [8, 9, 20],
+        [6, 18, 38],
+        [0, 36, 47],
+        [2, 45, 11],
+        [29, 26, 15],
+        [27, 44, 24],
+        [33, 53, 42],
+        [35, 17, 51],
+    ];
+
+    const CORN_FACES: [[Face; 3]; 8] = [
+        [Up, Right, Front],
+        [Up, Front, Left],
+        [Up, Left, Back],
+        [Up, Back, Right],
+        [Down, Front, Right],
+        [Down, Left, Front],
+        [Down, Back, Left],
+        [Down, Right, Back],
+    ];
+
+    const EDGE_FACELETS: [[usize; 2]; 12] = [
+        [5, 10],
+        [7, 19],
+        [3, 37],
+        [1, 46],
+        [32, 16],
+        [28, 25],
+        [30, 43],
+        [34, 52],
+        [23, 12],
+        [21, 41],
+        [50, 39],
+        [48, 14],
+    ];
+
+    const EDGE_FACES: [[Face; 2]; 12] = [
+        [Up, Right],
+        [Up, Front],
+        [Up, Left],
+        [Up, Back],
+        [Down, Right],
+        [Down, Front],
+        [Down, Left],
+        [Down, Back],
+        [Front, Right],
+        [Front, Left],
+        [Back, Left],
+        [Back, Right],
+    ];
+
     pub const MOV_SET: [Move; 18] = [
-        Move(Left, Ccw, Dual),
-        Move(Right, Ccw, Dual),
-        Move(Front, Ccw, Dual),
-        Move(Back, Ccw, Dual),
-        Move(Up, Ccw, Dual),
-        Move(Down, Ccw, Dual),
-        Move(Left, Ccw, Single),
-        Move(Right, Ccw, Single),
-        Move(Left, Cw, Single),
-        Move(Right, Cw, Single),
-        Move(Front, Ccw, Single),
-        Move(Back, Ccw, Single),
-        Move(Front, Cw, Single),
-        Move(Back, Cw, Single),
-        Move(Up, Ccw, Single),
-        Move(Down, Ccw, Single),
-        Move(Up, Cw, Single),
-        Move(Down, Cw, Single),
+        Move(Outer(Left), Ccw, Dual),
+        Move(Outer(Right), Ccw, Dual),
+        Move(Outer(Front), Ccw, Dual),
+        Move(Outer(Back), Ccw, Dual),
+        Move(Outer(Up), Ccw, Dual),
+        Move(Outer(Down), Ccw, Dual),
+        Move(Outer(Left), Ccw, Single),
+        Move(Outer(Right), Ccw, Single),
+        Move(Outer(Left), Cw, Single),
+        Move(Outer(Right), Cw, Single),
+        Move(Outer(Front), Ccw, Single),
+        Move(Outer(Back), Ccw, Single),
+        Move(Outer(Front), Cw, Single),
+        Move(Outer(Back), Cw, Single),
+        Move(Outer(Up), Ccw, Single),
+        Move(Outer(Down), Ccw, Single),
+        Move(Outer(Up), Cw, Single),
+        Move(Outer(Down), Cw, Single),
     ];
 
     pub fn new() -> Self {
+        Self::with_scheme(Scheme::default())
+    }
+
+    pub fn with_scheme(scheme: Scheme) -> Self {
         Self {
             ids: (0..27).collect(),
             subs: [
@@ -115,36 +213,105 @@ impl Cube {
                 Edge([Front, Down], [White, Green]),
                 Corner([Right, Front, Down], [Red, White, Green]),
             ],
-            movs: Vec::new(),
+            movs: Algorithm::new(),
+            undone: Algorithm::new(),
+            scheme,
+            palette: Palette::default(),
         }
     }
 
-    fn sub_2_str(&self, id: Id, face: Face) -> String {
+    pub(crate) fn sub_2_col(&self, id: Id, face: Face) -> MyColor {
         match self.subs[id] {
             Center(_, col) => col,
             Edge(dir, col) => col[dir.iter().position(|d| *d == face).unwrap()],
             Corner(dir, col) => col[dir.iter().position(|d| *d == face).unwrap()],
             _ => Void,
         }
-        .to_string()
     }
 
-    fn row_2_str(&self, pos: &[usize], face: Face, rev: bool) -> String {
+    fn sub_2_str(&self, id: Id, face: Face, marked: bool) -> String {
+        self.scheme
+            .color(self.sub_2_col(id, face))
+            .render_marked(self.palette, marked)
+    }
+
+    fn row_2_str(&self, pos: &[usize], face: Face, rev: bool, marked: &[Id]) -> String {
         let i = if rev { [2, 1, 0] } else { [0, 1, 2] };
 
-        format!(
-            "{}{}{}",
-            self.sub_2_str(self.ids[pos[i[0]]], face),
-            self.sub_2_str(self.ids[pos[i[1]]], face),
-            self.sub_2_str(self.ids[pos[i[2]]], face),
-        )
+        i.iter()
+            .map(|i| self.sub_2_str(self.ids[pos[*i]], face, marked.contains(&pos[*i])))
+            .collect()
     }
 
-    fn rotate_dir(dir: &mut Face, face: Face, chain: &[Face], step: isize) {
-        if *dir != face {
-            *dir = chain[((chain.iter().position(|x| x == dir).unwrap() + chain.len()) as isize
-                + step) as usize
-                % 4];
+    pub fn net(&self, marked: &[Id]) -> String {
+        let mut result = String::with_capacity(0x100);
+
+        for line in Self::FACE_MAP[Up as usize].chunks(3) {
+            result.push_str(&format!(
+                "         {}\n",
+                self.row_2_str(line, Up, false, marked)
+            ));
+        }
+        result.push('\n');
+        for (l, (f, (r, b))) in Self::FACE_MAP[Left as usize].chunks(3).zip(
+            Self::FACE_MAP[Front as usize].chunks(3).zip(
+                Self::FACE_MAP[Right as usize]
+                    .chunks(3)
+                    .zip(Self::FACE_MAP[Back as usize].chunks(3)),
+            ),
+        ) {
+            result.push_str(&format!(
+                " {}  {}  {}  {}\n",
+                self.row_2_str(l, Left, false, marked),
+                self.row_2_str(f, Front, false, marked),
+                self.row_2_str(r, Right, true, marked),
+                self.row_2_str(b, Back, true, marked),
+            ));
+        }
+        result.push('\n');
+        for line in Self::FACE_MAP[Down as usize].chunks(3).rev() {
+            result.push_str(&format!(
+                "         {}\n",
+                self.row_2_str(line, Down, false, marked),
+            ));
+        }
+        result
+    }
+
+    pub fn layer_pos(layer: Layer) -> Vec<Id> {
+        match layer {
+            Outer(face) => Self::FACE_MAP[face as usize].to_vec(),
+            Inner(slice) => Self::SLICE_MAP[slice as usize].to_vec(),
+            Wide(face) => [
+                Self::FACE_MAP[face as usize],
+                Self::SLICE_MAP[face.slice() as usize],
+            ]
+            .concat(),
+            Whole(_) => (0..27).collect(),
+        }
+    }
+
+    pub(crate) fn facelet_pos(face: Face) -> Vec<Id> {
+        let mut rows: Vec<&[Id]> = Self::FACE_MAP[face as usize].chunks(3).collect();
+
+        if face == Down {
+            rows.reverse();
+        }
+        rows.iter()
+            .flat_map(|row| {
+                let mut row = row.to_vec();
+
+                if face == Right || face == Back {
+                    row.reverse();
+                }
+                row
+            })
+            .collect()
+    }
+
+    fn rotate_dir(dir: &mut Face, chain: &[Face], step: isize) {
+        if let Some(i) = chain.iter().position(|x| x == dir) {
+            *dir = chain[((i + chain.len()) as isize + step) as usize % 4];
         }
     }
 
@@ -156,26 +323,26 @@ impl Cube {
         }];
 
         match &mut self.subs[id] {
+            Center(dir, _) => Self::rotate_dir(dir, chain, step),
             Edge(dir, _) => {
                 for d in dir {
-                    Self::rotate_dir(d, face, chain, step);
+                    Self::rotate_dir(d, chain, step);
                 }
             }
             Corner(dir, _) => {
                 for d in dir {
-                    Self::rotate_dir(d, face, chain, step);
+                    Self::rotate_dir(d, chain, step);
                 }
             }
             _ => (),
         }
     }
 
-    pub fn rotate(&mut self, mov: Move, mem: bool) {
-        let Move(face, rot, typ) = mov;
-        let rev = match (face, rot) {
-            (Front, Cw) | (Back, Ccw) | (Up, Cw) | (Down, Ccw) | (Left, Cw) | (Right, Ccw) => true,
-            _ => false,
-        };
+    fn turn(&mut self, layer: &[Id; 9], face: Face, rot: Rotation, typ: RotType) {
+        let rev = matches!(
+            (face, rot),
+            (Front, Cw) | (Back, Ccw) | (Up, Cw) | (Down, Ccw) | (Left, Cw) | (Right, Ccw)
+        );
         let win_size = if let Dual = typ { 3 } else { 2 };
 
         for chain in [[0_usize, 2, 8, 6], [1, 5, 7, 3]] {
@@ -184,87 +351,546 @@ impl Cube {
             } else {
                 Box::new(chain.windows(win_size)) as Box<dyn Iterator<Item = &[usize]>>
             } {
-                self.ids.swap(
-                    Self::FACE_MAP[face as usize][swap[0]],
-                    Self::FACE_MAP[face as usize][swap[swap.len() - 1]],
-                );
+                self.ids.swap(layer[swap[0]], layer[swap[swap.len() - 1]]);
             }
         }
-        for pos in Self::FACE_MAP[face as usize] {
+        for pos in layer {
             self.rotate_sub(
-                self.ids[pos],
+                self.ids[*pos],
                 face,
                 if rev { 1 } else { -1 } * if let Dual = typ { 2 } else { 1 },
             );
         }
+    }
+
+    pub fn rotate(&mut self, mov: Move, mem: bool) {
+        let Move(layer, rot, typ) = mov;
+
+        match layer {
+            Outer(face) => self.turn(&Self::FACE_MAP[face as usize], face, rot, typ),
+            Inner(slice) => self.turn(&Self::SLICE_MAP[slice as usize], slice.face(), rot, typ),
+            Wide(face) => {
+                self.turn(&Self::FACE_MAP[face as usize], face, rot, typ);
+                self.turn(&Self::SLICE_MAP[face.slice() as usize], face, rot, typ);
+            }
+            Whole(axis) => {
+                let face = axis.face();
+
+                for layer in [
+                    Self::FACE_MAP[face as usize],
+                    Self::SLICE_MAP[face.slice() as usize],
+                    Self::FACE_MAP[face.opposite() as usize],
+                ] {
+                    self.turn(&layer, face, rot, typ);
+                }
+            }
+        }
         if mem {
             self.movs.push(mov);
+            self.undone.clear();
         }
     }
 
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.movs.pop()?;
+
+        self.rotate(mv.inv(), false);
+        self.undone.push(mv);
+        Some(mv)
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        let mv = self.undone.pop()?;
+
+        self.rotate(mv, false);
+        self.movs.push(mv);
+        Some(mv)
+    }
+
+    pub fn history(&self) -> Algorithm {
+        self.movs
+            .concat(&self.undone.iter().rev().cloned().collect())
+    }
+
+    pub fn seek(&mut self, pos: usize) {
+        while self.movs.len() > pos && self.undo().is_some() {}
+        while self.movs.len() < pos && self.redo().is_some() {}
+    }
+
     pub fn mov_parser(mov: &str) -> Result<Move, String> {
-        match mov {
-            "U" => Ok(Move(Up, Cw, Single)),
-            "U2" => Ok(Move(Up, Cw, Dual)),
-            "U'" => Ok(Move(Up, Ccw, Single)),
-            "D" => Ok(Move(Down, Cw, Single)),
-            "D2" => Ok(Move(Down, Cw, Dual)),
-            "D'" => Ok(Move(Down, Ccw, Single)),
-            "F" => Ok(Move(Front, Cw, Single)),
-            "F2" => Ok(Move(Front, Cw, Dual)),
-            "F'" => Ok(Move(Front, Ccw, Single)),
-            "B" => Ok(Move(Back, Cw, Single)),
-            "B2" => Ok(Move(Back, Cw, Dual)),
-            "B'" => Ok(Move(Back, Ccw, Single)),
-            "L" => Ok(Move(Left, Cw, Single)),
-            "L2" => Ok(Move(Left, Cw, Dual)),
-            "L'" => Ok(Move(Left, Ccw, Single)),
-            "R" => Ok(Move(Right, Cw, Single)),
-            "R2" => Ok(Move(Right, Cw, Dual)),
-            "R'" => Ok(Move(Right, Ccw, Single)),
+        let mut chars = mov.chars();
+        let mut layer = chars.next().and_then(Layer::from_letter);
+
+        if let (Some(Outer(face)), Some('w')) = (layer, chars.clone().next()) {
+            layer = Some(Wide(face));
+            chars.next();
+        }
+        match (layer, chars.as_str()) {
+            (Some(layer), "") => Ok(Move(layer, Cw, Single)),
+            (Some(layer), "2") => Ok(Move(layer, Cw, Dual)),
+            (Some(layer), "'") => Ok(Move(layer, Ccw, Single)),
             _ => Err(format!("Face \"{}\" is not recognized", mov)),
         }
     }
 
-    pub fn from_str(s: &str, disp: bool) -> Self {
+    pub fn from_facelets(s: &str) -> Result<Self, CubeError> {
+        let letters: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let solved = Cube::new();
         let mut result = Cube::new();
-        let mut disp_res = String::new();
+        let mut stickers: Vec<Vec<(Face, MyColor)>> = vec![Vec::new(); 27];
+        let mut used = [false; 27];
 
-        for mv in s.split_whitespace().map(|m| Self::mov_parser(m).unwrap()) {
-            std::fmt::write(&mut disp_res, format_args!("{} ", mv)).unwrap();
-            result.rotate(mv, true);
+        if letters.len() != 54 {
+            return Err(FaceletCount(letters.len()));
+        }
+        for (face, chunk) in Self::FACELET_ORDER.iter().zip(letters.chunks(9)) {
+            for (pos, letter) in Self::facelet_pos(*face).into_iter().zip(chunk) {
+                let col = match Face::from_letter(*letter) {
+                    Some(f) => MyColor::COL_SET[f as usize],
+                    None => return Err(UnknownFacelet(*letter)),
+                };
+                stickers[pos].push((*face, col));
+            }
         }
-        if disp {
-            println!("{}{}", "MOVES: ".bright_green(), disp_res);
+        for (pos, stk) in stickers.iter().enumerate() {
+            let dir_of = |col: &MyColor| stk.iter().find(|(_, c)| c == col).unwrap().0;
+            let id = solved
+                .subs
+                .iter()
+                .position(|sub| {
+                    let cols: &[MyColor] = match sub {
+                        Center(_, col) => std::slice::from_ref(col),
+                        Edge(_, cols) => cols,
+                        Corner(_, cols) => cols,
+                        Core => &[],
+                    };
+
+                    cols.len() == stk.len()
+                        && cols
+                            .iter()
+                            .all(|col| stk.iter().filter(|(_, c)| c == col).count() == 1)
+                })
+                .ok_or_else(|| {
+                    UnknownPiece(stk.iter().map(|(_, c)| Self::col_2_letter(*c)).collect())
+                })?;
+
+            if used[id] {
+                return Err(DuplicatedPiece(
+                    stk.iter().map(|(_, c)| Self::col_2_letter(*c)).collect(),
+                ));
+            }
+            used[id] = true;
+            result.ids[pos] = id;
+            result.subs[id] = match &solved.subs[id] {
+                Center(_, col) => Center(stk[0].0, *col),
+                Edge(_, cols) => Edge([dir_of(&cols[0]), dir_of(&cols[1])], *cols),
+                Corner(_, cols) => Corner(
+                    [dir_of(&cols[0]), dir_of(&cols[1]), dir_of(&cols[2])],
+                    *cols,
+                ),
+                Core => Core,
+            };
         }
-        result
+        Ok(result)
+    }
+
+    fn col_2_letter(col: MyColor) -> char {
+        Face::FACE_SET[MyColor::COL_SET.iter().position(|c| *c == col).unwrap()]
+            .to_string()
+            .remove(0)
     }
 
-    pub fn from_rand(mov_nb: usize, group: usize, disp: bool) -> Self {
+    pub fn to_facelets(&self) -> String {
+        Self::FACELET_ORDER
+            .iter()
+            .flat_map(|face| {
+                Self::facelet_pos(*face)
+                    .into_iter()
+                    .map(|pos| Self::col_2_letter(self.sub_2_col(self.ids[pos], *face)))
+            })
+            .collect()
+    }
+
+    fn check_slots(&self) -> Result<(), CubeError> {
+        let solved = Cube::new();
+        let mut used = [false; 27];
+
+        for (pos, id) in self.ids.iter().enumerate() {
+            if *id >= 27 || used[*id] {
+                return Err(MisplacedPiece(pos));
+            }
+            used[*id] = true;
+
+            let mut faces: Vec<Face> = Face::FACE_SET
+                .iter()
+                .filter(|f| Self::FACE_MAP[**f as usize].contains(&pos))
+                .cloned()
+                .collect();
+            let mut dirs = match (&self.subs[*id], &solved.subs[pos]) {
+                (Center(dir, _), Center(..)) => vec![*dir],
+                (Edge(dirs, _), Edge(..)) => dirs.to_vec(),
+                (Corner(dirs, _), Corner(..)) => dirs.to_vec(),
+                (Core, Core) => vec![],
+                _ => return Err(MisplacedPiece(pos)),
+            };
+
+            faces.sort_by_key(|f| *f as usize);
+            dirs.sort_by_key(|f| *f as usize);
+            if faces != dirs {
+                return Err(MisplacedPiece(pos));
+            }
+        }
+        Ok(())
+    }
+
+    fn parity(perm: &[usize]) -> usize {
+        let mut result = 0;
+
+        for i in 0..perm.len() {
+            for j in i + 1..perm.len() {
+                if perm[i] > perm[j] {
+                    result += 1;
+                }
+            }
+        }
+        result % 2
+    }
+
+    pub fn orientation(&self) -> Option<Algorithm> {
+        let centers: Vec<Id> = Self::FACE_MAP.iter().map(|f| f[4]).collect();
+
+        for up in ["", "x", "x2", "x'", "z", "z'"] {
+            for front in ["", "y", "y2", "y'"] {
+                let movs: Algorithm = [up, front]
+                    .iter()
+                    .filter(|m| !m.is_empty())
+                    .map(|m| Self::mov_parser(m).unwrap())
+                    .collect();
+                let mut cube = self.clone();
+
+                for mv in &movs {
+                    cube.rotate(*mv, false);
+                }
+                if centers.iter().all(|pos| cube.ids[*pos] == *pos) {
+                    return Some(movs);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn validate(&self) -> Result<(), CubeError> {
+        self.check_slots()?;
+
+        let mut cube = self.clone();
+
+        for mv in self.orientation().ok_or(MisplacedCenters)? {
+            cube.rotate(mv, false);
+        }
+
+        let facelets: Vec<char> = cube.to_facelets().chars().collect();
+        let faces: Vec<Face> = facelets
+            .iter()
+            .map(|c| Face::from_letter(*c).unwrap())
+            .collect();
+        let mut corn_perm = Vec::with_capacity(8);
+        let mut edge_perm = Vec::with_capacity(12);
+        let mut twist = 0;
+        let mut flip = 0;
+
+        for fcs in &Self::CORN_FACELETS {
+            let letters: String = fcs.iter().map(|i| facelets[*i]).collect();
+            let ori = (0..3)
+                .find(|o| matches!(faces[fcs[*o]], Up | Down))
+                .ok_or_else(|| UnknownPiece(letters.clone()))?;
+            let corn = Self::CORN_FACES
+                .iter()
+                .position(|c| {
+                    c[0] == faces[fcs[ori]]
+                        && c[1] == faces[fcs[(ori + 1) % 3]]
+                        && c[2] == faces[fcs[(ori + 2) % 3]]
+                })
+                .ok_or_else(|| UnknownPiece(letters.clone()))?;
+
+            if corn_perm.contains(&corn) {
+                return Err(DuplicatedPiece(letters));
+            }
+            corn_perm.push(corn);
+            twist += ori;
+        }
+        for fcs in &Self::EDGE_FACELETS {
+            let letters: String = fcs.iter().map(|i| facelets[*i]).collect();
+            let (edge, ori) = Self::EDGE_FACES
+                .iter()
+                .enumerate()
+                .find_map(|(i, e)| {
+                    if e[0] == faces[fcs[0]] && e[1] == faces[fcs[1]] {
+                        Some((i, 0))
+                    } else if e[0] == faces[fcs[1]] && e[1] == faces[fcs[0]] {
+                        Some((i, 1))
+                    } else {
+                        None
+                    }
+                })
+                .ok_or_else(|| UnknownPiece(letters.clone()))?;
+
+            if edge_perm.contains(&edge) {
+                return Err(DuplicatedPiece(letters));
+            }
+            edge_perm.push(edge);
+            flip += ori;
+        }
+        if twist % 3 != 0 {
+            Err(TwistedCorner(twist % 3))
+        } else if flip % 2 != 0 {
+            Err(FlippedEdge)
+        } else if Self::parity(&corn_perm) != Self::parity(&edge_perm) {
+            Err(OddParity)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn from_rand(mov_nb: usize, group: usize) -> Self {
         let mut result = Cube::new();
-        let mut lst_mv: Option<Move> = None;
         let mut rng = rand::thread_rng();
-        let mut disp_res = String::new();
 
         for _ in 0..mov_nb {
             loop {
                 let mv = *Cube::MOV_SET[..Cube::MOV_SET.len() - group * 4]
                     .choose(&mut rng)
                     .unwrap();
-                if let Some(Move(face, ..)) = lst_mv {
-                    if face == mv.0 {
-                        continue;
-                    }
+                let cancels = result
+                    .movs
+                    .iter()
+                    .rev()
+                    .take_while(|prev| prev.0.axis() == mv.0.axis())
+                    .any(|prev| prev.0 == mv.0);
+
+                if !cancels {
+                    result.rotate(mv, true);
+                    break;
                 }
-                lst_mv = Some(mv);
-                std::fmt::write(&mut disp_res, format_args!("{} ", mv)).unwrap();
-                result.rotate(mv, true);
-                break;
             }
         }
-        if disp {
-            println!("{}{}", "MOVES: ".bright_green(), disp_res);
-        }
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    fn edit(changes: &[(usize, char)]) -> String {
+        let mut facelets: Vec<char> = SOLVED.chars().collect();
+
+        for (i, c) in changes {
+            facelets[*i] = *c;
+        }
+        facelets.into_iter().collect()
+    }
+
+    fn check(facelets: &str) -> Result<(), CubeError> {
+        Cube::from_facelets(facelets)?.validate()
+    }
+
+    fn apply(mut cube: Cube, movs: &str) -> Cube {
+        for mv in Cube::alg_parser(movs).unwrap() {
+            cube.rotate(mv, true);
+        }
+        cube
+    }
+
+    fn turned(movs: &str) -> Cube {
+        apply(Cube::new(), movs)
+    }
+
+    #[test]
+    fn facelets_round_trip() {
+        let mut movs: Vec<Move> = Vec::new();
+
+        for layer in Face::FACE_SET
+            .iter()
+            .flat_map(|f| [Outer(*f), Wide(*f)])
+            .chain(Slice::SLICE_SET.iter().map(|s| Inner(*s)))
+            .chain(Axis::AXIS_SET.iter().map(|a| Whole(*a)))
+        {
+            for q in 1..4 {
+                movs.push(Move::from_quarters(layer, q).unwrap());
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..2000 {
+            let mut cube = Cube::new();
+
+            for _ in 0..30 {
+                cube.rotate(*movs.choose(&mut rng).unwrap(), false);
+            }
+
+            let imported = Cube::from_facelets(&cube.to_facelets()).unwrap();
+
+            assert_eq!(imported, cube);
+            assert_eq!(imported.to_facelets(), cube.to_facelets());
+        }
+    }
+
+    #[test]
+    fn solved_is_valid() {
+        assert_eq!(check(SOLVED), Ok(()));
+    }
+
+    #[test]
+    fn facelet_count() {
+        assert_eq!(check(&SOLVED[1..]), Err(FaceletCount(53)));
+    }
+
+    #[test]
+    fn unknown_facelet() {
+        assert_eq!(check(&edit(&[(0, 'X')])), Err(UnknownFacelet('X')));
+    }
+
+    #[test]
+    fn mirrored_corner() {
+        // URF corner with its R and F stickers swapped
+        assert_eq!(
+            check(&edit(&[(9, 'F'), (20, 'R')])),
+            Err(UnknownPiece("UFR".to_string()))
+        );
+    }
+
+    #[test]
+    fn duplicated_piece() {
+        // UF edge replaced by a second UR edge
+        assert!(matches!(
+            check(&edit(&[(19, 'R')])),
+            Err(DuplicatedPiece(_))
+        ));
+    }
+
+    #[test]
+    fn misplaced_piece() {
+        let mut cube = Cube::new();
+
+        cube.ids.swap(0, 1);
+        assert!(matches!(cube.validate(), Err(MisplacedPiece(_))));
+    }
+
+    #[test]
+    fn misplaced_centers() {
+        assert_eq!(check(&edit(&[(4, 'R'), (13, 'U')])), Err(MisplacedCenters));
+    }
+
+    #[test]
+    fn twisted_corner() {
+        // URF corner turned clockwise in place
+        assert!(matches!(
+            check(&edit(&[(8, 'F'), (9, 'U'), (20, 'R')])),
+            Err(TwistedCorner(_))
+        ));
+    }
+
+    #[test]
+    fn flipped_edge() {
+        assert_eq!(check(&edit(&[(5, 'R'), (10, 'U')])), Err(FlippedEdge));
+    }
+
+    #[test]
+    fn odd_parity() {
+        // UR and UF edges swapped
+        assert_eq!(check(&edit(&[(10, 'F'), (19, 'R')])), Err(OddParity));
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut cube = turned("R U F'");
+        let before = cube.clone();
+
+        assert_eq!(cube.undo(), Some(Cube::mov_parser("F'").unwrap()));
+        assert_eq!(cube, turned("R U"));
+        assert_eq!(cube.redo(), Some(Cube::mov_parser("F'").unwrap()));
+        assert_eq!(cube, before);
+        assert_eq!(cube.redo(), None);
+    }
+
+    #[test]
+    fn branching() {
+        let mut cube = turned("R U F'");
+
+        cube.undo();
+        cube.undo();
+        cube.rotate(Cube::mov_parser("D").unwrap(), true);
+        assert!(cube.undone.is_empty());
+        assert_eq!(cube.redo(), None);
+        assert_eq!(cube, turned("R D"));
+        assert_eq!(cube.history().notation(), "R D");
+    }
+
+    #[test]
+    fn seek() {
+        let mut cube = turned("R U F'");
+
+        cube.seek(1);
+        assert_eq!(cube, turned("R"));
+        cube.seek(100);
+        assert_eq!(cube, turned("R U F'"));
+        cube.seek(0);
+        assert_eq!(cube, Cube::new());
+        assert_eq!(cube.undo(), None);
+    }
+
+    #[test]
+    fn history() {
+        let mut cube = turned("R U F' D2");
+
+        cube.undo();
+        cube.undo();
+        assert_eq!(cube.movs.notation(), "R U");
+        assert_eq!(cube.undone.notation(), "D2 F'");
+        assert_eq!(cube.history().notation(), "R U F' D2");
+    }
+
+    #[test]
+    fn slices() {
+        assert_eq!(turned("M"), turned("R L' x'"));
+        assert_eq!(turned("E"), turned("U D' y'"));
+        assert_eq!(turned("S"), turned("F' B z"));
+        assert_eq!(turned("M2 E'"), turned("M M E E E"));
+    }
+
+    #[test]
+    fn whole_cube() {
+        assert_eq!(turned("x"), turned("Rw L'"));
+        assert_eq!(turned("y"), turned("Uw D'"));
+        assert_eq!(turned("z"), turned("Fw B'"));
+    }
+
+    #[test]
+    fn orientation() {
+        let centers: Vec<Id> = Cube::FACE_MAP.iter().map(|f| f[4]).collect();
+
+        for movs in ["x R y2 Uw", "M E S"] {
+            let mut cube = turned(movs);
+
+            assert!(!centers.iter().all(|pos| cube.ids[*pos] == *pos));
+            for mv in &cube.orientation().unwrap() {
+                cube.rotate(*mv, false);
+            }
+            assert!(centers.iter().all(|pos| cube.ids[*pos] == *pos));
+            assert_eq!(cube.validate(), Ok(()));
+        }
+        assert_eq!(Cube::new().orientation(), Some(Algorithm::new()));
+    }
+
+    #[test]
+    fn wide() {
+        assert_eq!(turned("Rw"), turned("R M'"));
+        assert_eq!(turned("Uw"), turned("U E'"));
+        assert_eq!(turned("Fw"), turned("F S"));
+        assert_eq!(turned("r u' f2"), turned("Rw Uw' Fw2"));
+    }
+}
@@ -1,13 +1,11 @@
 use super::*;
 use crossbeam::thread;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 pub struct Extractor {
     cube: Cube,
-    mov_stack: Vec<Move>,
 }
 
 impl Extractor {
@@ -48,23 +46,12 @@ impl Extractor {
 
     const CORN_ID: [Id; 8] = [0, 2, 6, 8, 18, 20, 24, 26];
 
-    pub fn new(cube: Cube) -> Self {
-        Self {
-            cube,
-            mov_stack: Vec::with_capacity(128),
-        }
-    }
-
-    fn do_mov(&mut self, mv: Move) {
-        self.mov_stack.push(mv);
-        self.cube.rotate(mv, false);
-    }
-
-    fn undo_mov(&mut self) {
-        let Move(face, rot, typ) = self.mov_stack.pop().unwrap();
+    const G3_SEEDS: &'static str = include_str!("g3_seeds");
 
-        self.cube
-            .rotate(Move(face, if let Cw = rot { Ccw } else { Cw }, typ), false);
+    pub fn new(mut cube: Cube) -> Self {
+        cube.movs = Algorithm(Vec::with_capacity(128));
+        cube.undone.clear();
+        Self { cube }
     }
 
     //12bit key
@@ -81,10 +68,9 @@ impl Extractor {
                         .iter()
                         .enumerate()
                         .find_map(|(face_j, f)| {
-                            match col.iter().position(|c| MyColor::COL_SET[*f as usize] == *c) {
-                                Some(col_i) => Some((face_j, col_i)),
-                                None => None,
-                            }
+                            col.iter()
+                                .position(|c| MyColor::COL_SET[*f as usize] == *c)
+                                .map(|col_i| (face_j, col_i))
                         })
                         .unwrap();
 
@@ -131,7 +117,7 @@ impl Extractor {
                         (result << 1)
                             | (cols[1] != MyColor::COL_SET[dirs[1] as usize]
                                 && cols[1]
-                                    != MyColor::COL_SET[if dirs[1] as usize % 2 == 0 {
+                                    != MyColor::COL_SET[if (dirs[1] as usize).is_multiple_of(2) {
                                         dirs[1] as usize + 1
                                     } else {
                                         dirs[1] as usize - 1
@@ -168,11 +154,16 @@ impl Extractor {
         result
     }
 
-    fn movs_2_rev_u8(movs: &Vec<Move>) -> Vec<u8> {
+    fn movs_2_rev_u8(movs: &[Move]) -> Vec<u8> {
         movs.iter()
             .rev()
-            .map(|Move(face, rot, typ)| {
-                ((*face as u8) << 2)
+            .map(|Move(layer, rot, typ)| {
+                let face = match layer {
+                    Outer(face) => *face,
+                    _ => panic!("Not a face move"),
+                };
+
+                ((face as u8) << 2)
                     | (((if *rot == Cw { Ccw } else { Cw }) as u8) << 1)
                     | (*typ as u8)
             })
@@ -186,19 +177,19 @@ impl Extractor {
         set_sz: usize,
         rank: usize,
     ) {
-        sol.ins_min(key_gen(&self.cube), Self::movs_2_rev_u8(&self.mov_stack));
+        sol.ins_min(key_gen(&self.cube), Self::movs_2_rev_u8(&self.cube.movs));
         if rank > 0 {
             for mv in Cube::MOV_SET[..set_sz].iter() {
-                if self.mov_stack.last().unwrap().0 != mv.0 {
-                    self.do_mov(*mv);
+                if self.cube.movs.last().unwrap().0 != mv.0 {
+                    self.cube.rotate(*mv, true);
                     self.rec_search(sol, key_gen, set_sz, rank - 1);
-                    self.undo_mov();
+                    self.cube.undo();
                 }
             }
         }
     }
 
-    fn mt_search(inf: &TableInfos, cub: Cube) -> HashMap<u64, Vec<u8>> {
+    fn mt_search(inf: &TableInfos, cub: Cube, tick: &(dyn Fn() + Sync)) -> HashMap<u64, Vec<u8>> {
         let map = thread::scope(|s| {
             let mut thrds = Vec::with_capacity(inf.set_sz);
             let mut result: HashMap<u64, Vec<u8>> = HashMap::with_capacity(inf.cap);
@@ -209,8 +200,9 @@ impl Extractor {
                     let mut table: HashMap<u64, Vec<u8>> = HashMap::with_capacity(inf.cap);
 
                     table.ins_min((inf.key_gen)(&cub), vec![]);
-                    extractor.do_mov(*mv);
+                    extractor.cube.rotate(*mv, true);
                     extractor.rec_search(&mut table, inf.key_gen, inf.set_sz, inf.rank - 1);
+                    tick();
                     table
                 }));
             }
@@ -225,48 +217,60 @@ impl Extractor {
         map
     }
 
-    pub fn table_search(table_ids: Vec<usize>) {
-        let mut inf;
-        let mut file;
+    pub fn print_progress(id: Id, done: usize, total: usize) {
+        eprint!("\rTable {} extraction: {}%", id, done * 100 / total.max(1));
+        if done == total {
+            eprintln!();
+        }
+    }
 
-        for id in table_ids {
-            inf = &Extractor::TAB_INF[id - 1];
-            file = format!("tabs/mt_table_{}", inf.id);
-            println!("Table {} extraction:", inf.id);
-            match id {
-                3 => {
-                    let mut seeds: Vec<Option<Cube>> =
-                        BufReader::new(File::open("src/g3_seeds").unwrap())
-                            .lines()
-                            .map(|l| Some(Cube::from_str(&l.unwrap(), false)))
-                            .collect();
-                    let map = thread::scope(|s| {
-                        let mut result: HashMap<u64, Vec<u8>> = HashMap::with_capacity(inf.cap);
+    pub fn table_extract(
+        inf: &TableInfos,
+        progress: &(dyn Fn(Id, usize, usize) + Sync),
+    ) -> Result<HashMap<u64, Vec<u8>>, TableError> {
+        let mut seeds: Vec<Option<Cube>> = match inf.id {
+            3 => Self::G3_SEEDS
+                .lines()
+                .map(|l| Some(l.parse().unwrap()))
+                .collect(),
+            _ => vec![Some(Cube::new())],
+        };
+        let total = seeds.len() * inf.set_sz;
+        let done = AtomicUsize::new(0);
+        let tick = || progress(inf.id, done.fetch_add(1, Ordering::SeqCst) + 1, total);
 
-                        for cubs in seeds.chunks_mut(2) {
-                            let mut thrds = Vec::with_capacity(2);
+        progress(inf.id, 0, total);
+        Ok(thread::scope(|s| {
+            let mut result: HashMap<u64, Vec<u8>> = HashMap::with_capacity(inf.cap);
 
-                            for cub in cubs {
-                                //supprimer le display
-                                println!("{}", cub.as_ref().unwrap());
-                                thrds.push(s.spawn(|_| Self::mt_search(inf, cub.take().unwrap())));
-                            }
+            for cubs in seeds.chunks_mut(2) {
+                let mut thrds = Vec::with_capacity(2);
 
-                            for thrd in thrds {
-                                for (key, val) in thrd.join().unwrap() {
-                                    result.ins_min(key, val);
-                                }
-                            }
-                        }
-                        result
-                    })
-                    .unwrap();
-                    map
+                for cub in cubs {
+                    thrds.push(s.spawn(|_| Self::mt_search(inf, cub.take().unwrap(), &tick)));
+                }
+
+                for thrd in thrds {
+                    for (key, val) in thrd.join().unwrap() {
+                        result.ins_min(key, val);
+                    }
                 }
-                _ => Self::mt_search(inf, Cube::new()),
             }
-            .save(&file, inf.key_sz);
-            println!("Extracted to file {}", file);
+            result
+        })
+        .unwrap())
+    }
+
+    pub fn table_search(
+        table_ids: Vec<usize>,
+        dir: &Path,
+        progress: &(dyn Fn(Id, usize, usize) + Sync),
+    ) -> Result<(), TableError> {
+        for id in table_ids {
+            let inf = &Extractor::TAB_INF[id - 1];
+
+            Self::table_extract(inf, progress)?.save(&inf.path(dir), inf)?;
         }
+        Ok(())
     }
 }
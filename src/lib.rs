@@ -0,0 +1,37 @@
+mod algorithm;
+mod bench;
+mod compressor;
+mod cube;
+mod extractor;
+mod notation;
+mod report;
+mod solver;
+mod svg;
+mod table;
+mod utils;
+
+use colored::*;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use Axis::*;
+use CubeError::*;
+use Face::*;
+use Layer::*;
+use Metric::*;
+use MyColor::*;
+use Palette::*;
+use RotType::*;
+use Rotation::*;
+use Slice::*;
+use SubCube::*;
+use TableError::*;
+
+pub use algorithm::*;
+pub use bench::*;
+pub use cube::*;
+pub use extractor::*;
+pub use notation::*;
+pub use report::*;
+pub use solver::*;
+pub use table::*;
+pub use utils::*;
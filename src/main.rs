@@ -1,28 +1,18 @@
-mod compressor;
-mod cube;
-mod extractor;
-mod table;
-mod utils;
+mod playback;
+mod repl;
 
 use clap::{App, Arg};
 use colored::*;
-use cube::*;
-use extractor::*;
-use rand::seq::SliceRandom;
-use std::collections::HashMap;
-use table::*;
-use utils::*;
-use Face::*;
-use MyColor::*;
-use RotType::*;
-use Rotation::*;
-use SubCube::*;
+use rubik_2::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 fn input_checker(input: &str) -> Result<(), String> {
-    for mov in input.split_whitespace() {
-        Cube::mov_parser(mov)?;
-    }
-    Ok(())
+    Cube::alg_parser(input)
+        .map(|_| ())
+        .map_err(|e| format!("{}\n\n{}", e, e.underline(input)))
 }
 
 fn new_app() -> App<'static> {
@@ -31,12 +21,19 @@ fn new_app() -> App<'static> {
         .arg(
             Arg::new("MOVES")
                 .validator(input_checker)
-                .exclusive(true)
-                .required_unless_present_any(&["rand", "new", "tab"])
+                .conflicts_with_all(&["rand", "new", "facelets", "batch", "bench"])
+                .required_unless_present_any([
+                    "rand", "new", "facelets", "batch", "bench", "repl", "tab",
+                ])
                 .help(
-                    "Face rotations splited by whitespaces.\n\
+                    "Algorithm made of face rotations (whitespaces are optional).\n\
                     U, D, F, B, L, R for Up, Down, Front, Back, Left and Right\n\
-                    (append 2 for half turn and ' for counterclockwise)",
+                    M, E, S for the slices turning like L, D and F\n\
+                    x, y, z for whole cube rotations turning like R, U and F\n\
+                    Uw, Dw, Fw, Bw, Lw, Rw (or u, d, f, b, l, r) for wide turns\n\
+                    (append 2 for half turn and ' for counterclockwise)\n\
+                    (A)n repeats A, (A)' inverts it, [A, B] is a commutator,\n\
+                    [A: B] a conjugate and // starts a comment",
                 ),
         )
         .arg(
@@ -46,7 +43,7 @@ fn new_app() -> App<'static> {
                 .help("<NB> of random moves")
                 .require_equals(true)
                 .value_name("NB")
-                .validator(|arg| usize::from_str_radix(arg, 10)),
+                .validator(|arg| arg.parse::<usize>()),
         )
         .arg(
             Arg::new("group")
@@ -65,11 +62,151 @@ fn new_app() -> App<'static> {
                 .conflicts_with("rand")
                 .help("Start with an unaltered cube"),
         )
+        .arg(
+            Arg::new("facelets")
+                .long("facelets")
+                .short('f')
+                .conflicts_with_all(&["rand", "new"])
+                .require_equals(true)
+                .value_name("STR")
+                .validator(|arg| Cube::from_facelets(arg).map(|_| ()))
+                .help(
+                    "Start from a cube state given as 54 facelets in URFDLB order\n\
+                    (one letter per sticker naming the face whose center has its color)",
+                ),
+        )
+        .arg(
+            Arg::new("batch")
+                .long("batch")
+                .short('b')
+                .conflicts_with_all(&["rand", "new", "facelets"])
+                .require_equals(true)
+                .value_name("FILE")
+                .help(
+                    "Solve one scramble or facelet string per line of <FILE>\n\
+                    (- for standard input) and print one solution per line;\n\
+                    exits with status 1 if any line cannot be parsed or solved",
+                ),
+        )
+        .arg(
+            Arg::new("bench")
+                .long("bench")
+                .conflicts_with_all(&["new", "facelets", "batch"])
+                .require_equals(true)
+                .value_name("NB")
+                .validator(|arg| arg.parse::<usize>())
+                .help(
+                    "Solve <NB> random cubes (shuffled with --rand moves, 25 by default)\n\
+                    and print move count and timing statistics",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .require_equals(true)
+                .value_name("FMT")
+                .possible_values(["net", "facelets", "json"])
+                .default_value("net")
+                .help(
+                    "Display cube states as a colored net or as facelet strings,\n\
+                    or print solutions and --bench reports as JSON documents",
+                ),
+        )
+        .arg(
+            Arg::new("scheme")
+                .long("scheme")
+                .require_equals(true)
+                .value_name("SCHEME")
+                .default_value("classic")
+                .validator(|arg| arg.parse::<Scheme>())
+                .help(
+                    "Color scheme of the cube: classic, western, japanese or six color\n\
+                    letters (W, Y, G, B, O, R) for the URFDLB faces",
+                ),
+        )
+        .arg(
+            Arg::new("palette")
+                .long("palette")
+                .require_equals(true)
+                .value_name("PAL")
+                .possible_values(["standard", "colorblind"])
+                .default_value("standard")
+                .help("Terminal colors of the net (colorblind adds color letters)"),
+        )
+        .arg(
+            Arg::new("svg")
+                .long("svg")
+                .conflicts_with_all(&["batch", "bench"])
+                .require_equals(true)
+                .value_name("DIR")
+                .help("Write an SVG image of the cube after each solving step into <DIR>"),
+        )
+        .arg(
+            Arg::new("svg-step")
+                .long("svg-step")
+                .require_equals(true)
+                .value_name("STEP")
+                .possible_values(["phase", "move"])
+                .default_value("phase")
+                .help("Write one SVG image per phase or per move"),
+        )
+        .arg(
+            Arg::new("iso")
+                .long("iso")
+                .requires("svg")
+                .help("Draw SVG images as an isometric view of the U, F and R faces"),
+        )
+        .arg(
+            Arg::new("play")
+                .long("play")
+                .conflicts_with_all(&["batch", "bench"])
+                .help("Play the solution move by move in the terminal"),
+        )
+        .arg(
+            Arg::new("delay")
+                .long("delay")
+                .require_equals(true)
+                .value_name("MS")
+                .validator(|s| s.parse::<u64>())
+                .default_value("500")
+                .help("Delay between two moves of the playback in milliseconds"),
+        )
+        .arg(
+            Arg::new("repl")
+                .long("repl")
+                .short('i')
+                .conflicts_with_all(&["batch", "bench", "play", "svg"])
+                .help("Start an interactive shell to turn and solve the cube"),
+        )
+        .arg(
+            Arg::new("tables-dir")
+                .long("tables-dir")
+                .require_equals(true)
+                .value_name("DIR")
+                .help(
+                    "Directory holding the lookup tables (default: $RUBIK_TABLES_DIR or\n\
+                    ~/.cache/rusted_rubiks); tables missing there are read from ./tabs\n\
+                    when the current directory has one (as in a source checkout)",
+                ),
+        )
+        .arg(
+            Arg::new("no-gen")
+                .long("no-gen")
+                .help("Fail instead of generating missing tables on disk"),
+        )
+        .arg(Arg::new("plain").long("plain").short('p').help(
+            "Print without ANSI colors: standard notation and a net of color letters\n\
+                    (default when the standard output is not a terminal)",
+        ))
         .arg(
             Arg::new("tab")
                 .long("tab")
                 .short('t')
-                .exclusive(true)
+                .conflicts_with_all(&[
+                    "MOVES", "rand", "group", "new", "facelets", "batch", "bench", "format", "svg",
+                    "svg-step", "iso", "play", "delay", "repl", "scheme", "palette", "plain",
+                    "no-gen",
+                ])
                 .require_equals(true)
                 .validator(|v| {
                     let mut tabs: Vec<&str> = v.split(',').collect();
@@ -77,13 +214,13 @@ fn new_app() -> App<'static> {
 
                     tabs.sort();
                     tabs.dedup();
-                    if len < 1 || len > 4 {
+                    if !(1..=4).contains(&len) {
                         return Err("too many tables");
                     } else if len != tabs.len() {
                         return Err("table duplicate");
                     } else {
                         for tab in tabs {
-                            if ["1", "2", "3", "4"].contains(&tab) == false {
+                            if !["1", "2", "3", "4"].contains(&tab) {
                                 return Err("invalid table number");
                             }
                         }
@@ -95,50 +232,341 @@ fn new_app() -> App<'static> {
         )
 }
 
-pub fn solve(cube: &mut Cube) {
-    for step in 1..5 {
-        let tab_inf = &Extractor::TAB_INF[step - 1];
-        let mut table: HashMap<u64, Vec<u8>> = HashMap::with_capacity(tab_inf.cap);
+fn cube_2_str(cube: &Cube, facelets: bool) -> String {
+    if facelets {
+        format!("{}\n", cube.to_facelets())
+    } else {
+        cube.to_string()
+    }
+}
 
-        table.load(&format!("tabs/mt_table_{}", step), tab_inf.key_sz);
-        print!(
-            "{}{}",
-            format!("PHASE {}: ", step).bright_green(),
-            table.exec((tab_inf.key_gen)(&cube), cube)
-        );
-        println!("\n\n{}", cube);
+fn print_solution(cube: &Cube, sol: &Solution, facelets: bool) {
+    let mut state = cube.clone();
+
+    if !sol.orientation.is_empty() {
+        print!("{}", "ORIENTATION: ".bright_green());
+        for mv in &sol.orientation {
+            print!("{} ", mv);
+            state.rotate(*mv, false);
+        }
+        println!("\n\n{}", cube_2_str(&state, facelets));
+    }
+    for (step, phase) in sol.phases.iter().enumerate() {
+        print!("{}", format!("PHASE {}: ", step + 1).bright_green());
+        for mv in &phase.movs {
+            print!("{} ", mv);
+            state.rotate(*mv, false);
+        }
+        println!("\n\n{}", cube_2_str(&state, facelets));
+    }
+    println!(
+        "{}{} ({} moves)",
+        "SOLUTION: ".bright_green(),
+        sol.movs(),
+        sol.len()
+    );
+}
+
+fn write_svgs(dir: &str, cube: &Cube, sol: &Solution, per_move: bool, iso: bool) {
+    let mut state = cube.clone();
+    let mut frames = vec![("start".to_string(), state.clone())];
+    let steps = std::iter::once(("orientation".to_string(), &sol.orientation)).chain(
+        sol.phases
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (format!("phase_{}", i + 1), &p.movs)),
+    );
+
+    for (name, movs) in steps {
+        for (i, mv) in movs.iter().enumerate() {
+            state.rotate(*mv, false);
+            if per_move {
+                frames.push((format!("{}_{:02}", name, i + 1), state.clone()));
+            }
+        }
+        if !per_move && !movs.is_empty() {
+            frames.push((name, state.clone()));
+        }
+    }
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        eprintln!("{} {}: {}", "error:".bright_red(), dir, err);
+        std::process::exit(1);
+    }
+    for (i, (name, frame)) in frames.iter().enumerate() {
+        let file = format!("{}/{:03}_{}.svg", dir, i, name);
+        let svg = if iso {
+            frame.to_svg_iso()
+        } else {
+            frame.to_svg()
+        };
+
+        if let Err(err) = std::fs::write(&file, svg) {
+            eprintln!("{} {}: {}", "error:".bright_red(), file, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_movs(cube: &Cube) {
+    println!("{}{}", "MOVES: ".bright_green(), cube.movs);
+}
+
+fn parse_cube(line: &str) -> Result<Cube, String> {
+    let letters: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let facelets = if letters.len() == 54 && letters.chars().all(|c| "URFDLB".contains(c)) {
+        Some(Cube::from_facelets(&letters))
+    } else {
+        None
+    };
+
+    match facelets {
+        Some(Ok(cube)) => Ok(cube),
+        Some(Err(err)) if line.split_whitespace().count() == 1 => Err(err.to_string()),
+        _ => line.parse::<Cube>().map_err(|e| match facelets {
+            Some(Err(err)) => err.to_string(),
+            _ => e.to_string(),
+        }),
+    }
+}
+
+#[cfg(feature = "embedded-tables")]
+fn solve_error(err: &SolveError) -> String {
+    match err {
+        SolveError::Table(id, _) => format!(
+            "{} (run with --tab={} --tables-dir=tabs and rebuild to replace it)",
+            err, id
+        ),
+        _ => err.to_string(),
+    }
+}
+
+#[cfg(not(feature = "embedded-tables"))]
+fn solve_error(err: &SolveError) -> String {
+    let dir = Solver::global().dir();
+
+    match err {
+        SolveError::Table(id, TableError::MissingFile(_)) => format!(
+            "{} (run with --tab={} --tables-dir={} to generate it)",
+            err,
+            id,
+            dir.display()
+        ),
+        SolveError::Table(id, _) => format!(
+            "{} (run with --tab={} --tables-dir={} to regenerate it)",
+            err,
+            id,
+            dir.display()
+        ),
+        _ => err.to_string(),
+    }
+}
+
+fn json_error(err: &str) -> String {
+    serde_json::json!({ "error": err }).to_string()
+}
+
+fn batch(path: &str, json: bool) {
+    let reader: Box<dyn BufRead> = if path == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(err) => {
+                eprintln!("{} {}: {}", "error:".bright_red(), path, err);
+                std::process::exit(1);
+            }
+        }
+    };
+    let mut inputs: Vec<Result<Cube, String>> = Vec::new();
+
+    for line in reader.split(b'\n') {
+        match line {
+            Ok(line) => inputs.push(
+                String::from_utf8(line)
+                    .map_err(|_| "Line is not valid UTF-8".to_string())
+                    .and_then(|l| parse_cube(l.trim_end_matches('\r'))),
+            ),
+            Err(err) => {
+                eprintln!("{} {}: {}", "error:".bright_red(), path, err);
+                std::process::exit(1);
+            }
+        }
+    }
+    let cubes: Vec<Cube> = inputs.iter().filter_map(|i| i.clone().ok()).collect();
+    let mut sols = Solver::global().solve_batch(&cubes).into_iter();
+    let mut failed = false;
+
+    for input in inputs {
+        let res = input.and_then(|cube| {
+            sols.next()
+                .unwrap()
+                .map(|sol| (cube, sol))
+                .map_err(|e| solve_error(&e))
+        });
+
+        failed |= res.is_err();
+        match (res, json) {
+            (Ok((cube, sol)), true) => println!(
+                "{}",
+                serde_json::to_string(&SolveReport::new(&cube, &sol)).unwrap()
+            ),
+            (Ok((_, sol)), false) => println!("{}", sol.movs()),
+            (Err(err), true) => println!("{}", json_error(&err)),
+            (Err(err), false) => println!("error: {}", err),
+        }
+    }
+    if failed {
+        std::process::exit(1);
     }
 }
 
 fn main() {
     let cmd = new_app().get_matches();
 
+    #[cfg(feature = "embedded-tables")]
+    if cmd.is_present("no-gen") || (cmd.is_present("tables-dir") && !cmd.is_present("tab")) {
+        eprintln!(
+            "{} --tables-dir and --no-gen only apply to --tab in a build with embedded tables",
+            "error:".bright_red()
+        );
+        std::process::exit(1);
+    }
+
+    if cmd.is_present("plain") || !io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+
+    let dir = cmd
+        .value_of("tables-dir")
+        .map_or_else(TableInfos::default_dir, PathBuf::from);
+
+    Solver::global().set_dir(&dir);
+    Solver::global().set_fallback_dir(Some(Path::new("tabs")));
+    Solver::global().set_progress(Extractor::print_progress);
+    Solver::global().set_warning(|err| {
+        eprintln!(
+            "{} {} (the table is only kept in memory)",
+            "warning:".bright_yellow(),
+            err
+        )
+    });
+    if cmd.is_present("no-gen") {
+        Solver::global().set_generate(false);
+    }
     if cmd.is_present("tab") {
         let tabs = cmd
             .value_of("tab")
             .unwrap()
-            .split(",")
+            .split(',')
             .collect::<Vec<&str>>();
-        Extractor::table_search(
-            tabs.iter()
-                .map(|t| usize::from_str_radix(t, 10).unwrap())
-                .collect(),
+        if let Err(err) = Extractor::table_search(
+            tabs.iter().map(|t| t.parse().unwrap()).collect(),
+            &dir,
+            &Extractor::print_progress,
+        ) {
+            eprintln!("{} {}", "error:".bright_red(), err);
+            std::process::exit(1);
+        }
+    } else if cmd.is_present("bench") {
+        let report = match BenchReport::run(
+            Solver::global(),
+            cmd.value_of("bench").unwrap().parse().unwrap(),
+            cmd.value_of("rand").unwrap_or("25").parse().unwrap(),
+            cmd.value_of("group").unwrap_or("0").parse().unwrap(),
+        ) {
+            Ok(report) => report,
+            Err(err) => {
+                eprintln!("{} {}", "error:".bright_red(), solve_error(&err));
+                std::process::exit(1);
+            }
+        };
+
+        if cmd.value_of("format") == Some("json") {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        } else {
+            println!("{}", report);
+        }
+    } else if cmd.is_present("batch") {
+        batch(
+            cmd.value_of("batch").unwrap(),
+            cmd.value_of("format") == Some("json"),
         );
     } else {
+        let json = cmd.value_of("format") == Some("json");
         let mut cube = if cmd.is_present("new") {
             Cube::new()
         } else {
-            if cmd.is_present("rand") {
-                Cube::from_rand(
-                    usize::from_str_radix(cmd.value_of("rand").unwrap(), 10).unwrap(),
-                    usize::from_str_radix(cmd.value_of("group").unwrap_or("0"), 10).unwrap(),
-                    true,
-                )
+            if cmd.is_present("facelets") {
+                Cube::from_facelets(cmd.value_of("facelets").unwrap()).unwrap()
+            } else if cmd.is_present("rand") {
+                let cube = Cube::from_rand(
+                    cmd.value_of("rand").unwrap().parse().unwrap(),
+                    cmd.value_of("group").unwrap_or("0").parse().unwrap(),
+                );
+
+                if !json {
+                    print_movs(&cube);
+                }
+                cube
+            } else if let Some(movs) = cmd.value_of("MOVES") {
+                let cube = movs.parse().unwrap();
+
+                if !json {
+                    print_movs(&cube);
+                }
+                cube
             } else {
-                Cube::from_str(cmd.value_of("MOVES").unwrap(), true)
+                Cube::new()
             }
         };
-        println!("\n{}", cube);
-        solve(&mut cube);
+        let facelets = cmd.value_of("format") == Some("facelets");
+
+        cube.scheme = cmd.value_of("scheme").unwrap().parse().unwrap();
+        cube.palette = cmd.value_of("palette").unwrap().parse().unwrap();
+        if cmd.is_present("repl") {
+            return repl::repl(cube, facelets);
+        }
+        if !json {
+            println!("\n{}", cube_2_str(&cube, facelets));
+        }
+        let res = solve(&mut cube.clone());
+
+        if let (Some(dir), Ok(sol)) = (cmd.value_of("svg"), &res) {
+            write_svgs(
+                dir,
+                &cube,
+                sol,
+                cmd.value_of("svg-step") == Some("move"),
+                cmd.is_present("iso"),
+            );
+        }
+        match (res, json) {
+            (Ok(sol), true) => println!(
+                "{}",
+                serde_json::to_string_pretty(&SolveReport::new(&cube, &sol)).unwrap()
+            ),
+            (Ok(sol), false) => {
+                if cmd.is_present("play") {
+                    if let Err(err) = playback::playback(
+                        &cube,
+                        &sol.movs(),
+                        Duration::from_millis(cmd.value_of("delay").unwrap().parse().unwrap()),
+                    ) {
+                        eprintln!("{} {}", "error:".bright_red(), err);
+                        std::process::exit(1);
+                    }
+                }
+                print_solution(&cube, &sol, facelets);
+            }
+            (Err(err), true) => {
+                println!("{}", json_error(&solve_error(&err)));
+                std::process::exit(1);
+            }
+            (Err(err), false) => {
+                eprintln!("{} {}", "error:".bright_red(), solve_error(&err));
+                std::process::exit(1);
+            }
+        }
     }
 }
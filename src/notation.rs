@@ -0,0 +1,349 @@
+use super::*;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub msg: String,
+    pub span: Range<usize>,
+}
+
+impl ParseError {
+    fn new(msg: String, span: Range<usize>) -> Self {
+        Self { msg, span }
+    }
+
+    pub fn underline(&self, src: &str) -> String {
+        let start = src[..self.span.start].chars().count();
+        let len = src[self.span.clone()].chars().count().max(1);
+
+        format!("{}\n{}{}", src, " ".repeat(start), "^".repeat(len))
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} at {}..{}", self.msg, self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+const MAX_LEN: usize = 10_000;
+const MAX_DEPTH: usize = 100;
+
+struct Parser<'a> {
+    src: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            chars: src.char_indices().collect(),
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.chars
+            .get(self.pos)
+            .map(|(i, _)| *i)
+            .unwrap_or(self.src.len())
+    }
+
+    fn get(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|(_, c)| *c)
+    }
+
+    fn skip_blanks(&mut self) {
+        while let Some(c) = self.get() {
+            if c.is_whitespace() {
+                self.pos += 1;
+            } else if self.src[self.offset()..].starts_with("//") {
+                while !matches!(self.get(), None | Some('\n')) {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_blanks();
+        self.get()
+    }
+
+    fn count(&mut self) -> Result<Option<usize>, ParseError> {
+        let start = self.offset();
+
+        while matches!(self.get(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.offset() {
+            return Ok(None);
+        }
+        match self.src[start..self.offset()].parse() {
+            Ok(0) | Err(_) => Err(ParseError::new(
+                "Invalid count".to_string(),
+                start..self.offset(),
+            )),
+            Ok(count) => Ok(Some(count)),
+        }
+    }
+
+    fn prime(&mut self) -> bool {
+        if self.get() == Some('\'') {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn invert(movs: &[Move]) -> Vec<Move> {
+        movs.iter().rev().map(|mv| mv.inv()).collect()
+    }
+
+    fn mov(&mut self) -> Result<Vec<Move>, ParseError> {
+        let start = self.offset();
+
+        self.pos += 1;
+        if self.get() == Some('w') {
+            self.pos += 1;
+        }
+
+        let base = &self.src[start..self.offset()];
+        let count = self.count()?.unwrap_or(1);
+        let prime = self.prime();
+        let span = start..self.offset();
+        let mv = Cube::mov_parser(base).map_err(|_| {
+            ParseError::new(
+                format!("Move \"{}\" is not recognized", &self.src[span.clone()]),
+                span.clone(),
+            )
+        })?;
+        let quarters = if prime { 4 - count % 4 } else { count };
+
+        Ok(Move::from_quarters(mv.0, quarters).into_iter().collect())
+    }
+
+    fn too_long(span: Range<usize>) -> ParseError {
+        ParseError::new(
+            format!("Algorithm expands to more than {} moves", MAX_LEN),
+            span,
+        )
+    }
+
+    fn suffix(&mut self, movs: Vec<Move>) -> Result<Vec<Move>, ParseError> {
+        let start = self.offset();
+        let count = self.count()?.unwrap_or(1);
+
+        if movs.len().saturating_mul(count) > MAX_LEN {
+            return Err(Self::too_long(start..self.offset()));
+        }
+
+        let movs = if self.prime() {
+            Self::invert(&movs)
+        } else {
+            movs
+        };
+
+        Ok(movs.repeat(count))
+    }
+
+    fn expect(&mut self, close: char, open: Range<usize>) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == close => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(ParseError::new(
+                format!("Missing closing \"{}\"", close),
+                open.start..self.offset(),
+            )),
+        }
+    }
+
+    fn group(&mut self) -> Result<Vec<Move>, ParseError> {
+        let open = self.offset()..self.offset() + 1;
+
+        self.pos += 1;
+
+        let movs = self.seq(&[')'])?;
+
+        self.expect(')', open)?;
+        self.suffix(movs)
+    }
+
+    fn bracket(&mut self) -> Result<Vec<Move>, ParseError> {
+        let open = self.offset()..self.offset() + 1;
+
+        self.pos += 1;
+
+        let a = self.seq(&[',', ':', ']'])?;
+        let sep = self.peek();
+
+        if !matches!(sep, Some(',') | Some(':')) {
+            return Err(ParseError::new(
+                "Expected \",\" or \":\" in brackets".to_string(),
+                open.start..self.offset(),
+            ));
+        }
+        self.pos += 1;
+
+        let b = self.seq(&[']'])?;
+
+        self.expect(']', open)?;
+
+        let mut movs = a.clone();
+
+        movs.extend(b.iter().cloned());
+        movs.extend(Self::invert(&a));
+        if sep == Some(',') {
+            movs.extend(Self::invert(&b));
+        }
+        self.suffix(movs)
+    }
+
+    fn nest(&mut self, open: char) -> Result<Vec<Move>, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ParseError::new(
+                format!("Brackets nested deeper than {} levels", MAX_DEPTH),
+                self.offset()..self.offset() + 1,
+            ));
+        }
+        self.depth += 1;
+
+        let movs = if open == '(' {
+            self.group()
+        } else {
+            self.bracket()
+        };
+
+        self.depth -= 1;
+        movs
+    }
+
+    fn seq(&mut self, closing: &[char]) -> Result<Vec<Move>, ParseError> {
+        let mut result = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if closing.contains(&c) {
+                break;
+            }
+
+            let start = self.offset();
+
+            result.extend(match c {
+                '(' | '[' => self.nest(c)?,
+                c if c.is_alphabetic() => self.mov()?,
+                c => {
+                    return Err(ParseError::new(
+                        format!("Unexpected \"{}\"", c),
+                        self.offset()..self.offset() + c.len_utf8(),
+                    ))
+                }
+            });
+            if result.len() > MAX_LEN {
+                return Err(Self::too_long(start..self.offset()));
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Cube {
+    pub fn alg_parser(s: &str) -> Result<Vec<Move>, ParseError> {
+        Parser::new(s).seq(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(s: &str) -> String {
+        Algorithm(Cube::alg_parser(s).unwrap()).notation()
+    }
+
+    fn error(s: &str) -> (String, Range<usize>) {
+        let err = Cube::alg_parser(s).unwrap_err();
+
+        (err.msg, err.span)
+    }
+
+    #[test]
+    fn moves() {
+        assert_eq!(
+            expand("R U' F2 R3 U5' x M2 Rw r'"),
+            "R U' F2 R' U' x M2 Rw Rw'"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        assert_eq!(expand("(R U)2"), "R U R U");
+        assert_eq!(expand("(R U)'"), "U' R'");
+        assert_eq!(expand("(R U2)2'"), "U2 R' U2 R'");
+    }
+
+    #[test]
+    fn brackets() {
+        assert_eq!(expand("[R, U]"), "R U R' U'");
+        assert_eq!(expand("[R: U]"), "R U R'");
+        assert_eq!(expand("[R, U]2"), "R U R' U' R U R' U'");
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(expand("[F: [R, U]]"), "F R U R' U' F'");
+        assert_eq!(expand("((R)2 U)'"), "U' R' R'");
+        assert_eq!(expand("[(R U)', D]"), "U' R' D R U D'");
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(expand("R // sexy\nU // move"), "R U");
+        assert_eq!(expand("// only a comment"), "");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("R Q2"),
+            ("Move \"Q2\" is not recognized".to_string(), 2..4)
+        );
+        assert_eq!(error("R (U"), ("Missing closing \")\"".to_string(), 2..4));
+        assert_eq!(
+            error("[R U]"),
+            ("Expected \",\" or \":\" in brackets".to_string(), 0..4)
+        );
+        assert_eq!(error("R }"), ("Unexpected \"}\"".to_string(), 2..3));
+        assert_eq!(error("R0"), ("Invalid count".to_string(), 1..2));
+        assert_eq!(error("(R)0"), ("Invalid count".to_string(), 3..4));
+    }
+
+    #[test]
+    fn too_long() {
+        let msg = format!("Algorithm expands to more than {} moves", MAX_LEN);
+
+        assert_eq!(error("(R)99999999999"), (msg.clone(), 3..14));
+        assert_eq!(error("R U (R U)5000"), (msg.clone(), 4..13));
+        assert_eq!(error("(R U)5000 R"), (msg, 10..11));
+        assert_eq!(Cube::alg_parser("(R U)5000").unwrap().len(), MAX_LEN);
+    }
+
+    #[test]
+    fn too_deep() {
+        let msg = format!("Brackets nested deeper than {} levels", MAX_DEPTH);
+        let deep = format!("{}R{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+
+        assert_eq!(expand(&deep), "R");
+        assert_eq!(error(&format!("[R: {}]", deep)), (msg.clone(), 103..104));
+        assert_eq!(error(&"(".repeat(10_000)), (msg, 100..101));
+    }
+}
@@ -0,0 +1,100 @@
+use colored::*;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use rubik_2::*;
+use std::io::{self, Write};
+use std::time::Duration;
+
+fn draw(
+    out: &mut impl Write,
+    cube: &Cube,
+    movs: &Algorithm,
+    step: usize,
+    paused: bool,
+) -> io::Result<()> {
+    let marked = match movs.get(step) {
+        Some(Move(layer, ..)) => Cube::layer_pos(*layer),
+        _ => Vec::new(),
+    };
+    let next = movs.get(step).map_or("-".to_string(), |mv| mv.to_string());
+
+    execute!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+    write!(
+        out,
+        "{}{}/{}   {}{}   {}\r\n\r\n{}\r\nspace: pause   right/n: next   left/p: previous   q: quit\r\n",
+        "MOVE: ".bright_green(),
+        step,
+        movs.len(),
+        "NEXT: ".bright_green(),
+        next,
+        if step == movs.len() {
+            "[DONE]"
+        } else if paused {
+            "[PAUSED]"
+        } else {
+            ""
+        },
+        cube.net(&marked).replace('\n', "\r\n"),
+    )?;
+    out.flush()
+}
+
+fn play(
+    out: &mut impl Write,
+    cube: &mut Cube,
+    movs: &Algorithm,
+    delay: Duration,
+) -> io::Result<()> {
+    let start = cube.movs.len();
+    let mut paused = false;
+
+    for mv in movs {
+        cube.rotate(*mv, true);
+    }
+    cube.seek(start);
+    loop {
+        let step = cube.movs.len() - start;
+
+        draw(out, cube, movs, step, paused)?;
+        if event::poll(delay)? {
+            if let Event::Key(KeyEvent { code, modifiers }) = event::read()? {
+                match code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(())
+                    }
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Right | KeyCode::Char('n') => {
+                        paused = true;
+                        cube.redo();
+                    }
+                    KeyCode::Left | KeyCode::Char('p') if step > 0 => {
+                        paused = true;
+                        cube.undo();
+                    }
+                    _ => (),
+                }
+            }
+        } else if !paused {
+            cube.redo();
+        }
+    }
+}
+
+pub fn playback(cube: &Cube, movs: &Algorithm, delay: Duration) -> io::Result<()> {
+    let mut out = io::stdout();
+    let mut cube = cube.clone();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+
+    let result = play(&mut out, &mut cube, movs, delay);
+
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
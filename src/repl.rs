@@ -0,0 +1,132 @@
+use super::{cube_2_str, print_movs, solve_error};
+use colored::*;
+use rubik_2::*;
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+const HELP: &str = "\
+<MOVES>             apply moves (e.g. R U R' U', (R U)3, [R, U])
+undo [NB]           undo the last move(s)
+redo [NB]           redo the last undone move(s)
+goto <NB>           go back or forward to move <NB> of the history
+reset               start again from a solved cube
+scramble <NB>       start from a cube shuffled with <NB> random moves
+solve               solve the cube and apply the solution
+show                print the cube
+facelets [CUBE]     print the cube as a facelet string or load one
+history             print the moves applied since the last reset and the undone ones
+help                print this help
+quit                leave the shell";
+
+fn fresh(cube: &Cube, new: Cube) -> Cube {
+    Cube {
+        scheme: cube.scheme,
+        palette: cube.palette,
+        ..new
+    }
+}
+
+fn exec(cube: &mut Cube, line: &str, facelets: bool) -> Result<bool, String> {
+    let mut words = line.split_whitespace();
+    let arg = |word: Option<&str>| -> Result<usize, String> {
+        let word = word.ok_or_else(|| "missing <NB>".to_string())?;
+
+        word.parse()
+            .map_err(|_| format!("\"{}\" is not a valid number", word))
+    };
+    let count = |word: Option<&str>| word.map_or(Ok(1), |w| arg(Some(w)));
+
+    match words.next() {
+        Some("undo") => {
+            for _ in 0..count(words.next())? {
+                if cube.undo().is_none() {
+                    break;
+                }
+            }
+        }
+        Some("redo") => {
+            for _ in 0..count(words.next())? {
+                if cube.redo().is_none() {
+                    break;
+                }
+            }
+        }
+        Some("goto") => cube.seek(arg(words.next())?),
+        Some("reset") => *cube = fresh(cube, Cube::new()),
+        Some("scramble") => {
+            *cube = fresh(cube, Cube::from_rand(arg(words.next())?, 0));
+            print_movs(cube);
+        }
+        Some("solve") => {
+            let sol = solve(&mut cube.clone()).map_err(|e| solve_error(&e))?;
+
+            println!(
+                "{}{} ({} moves)",
+                "SOLUTION: ".bright_green(),
+                sol.movs(),
+                sol.len()
+            );
+            for mv in sol.movs() {
+                cube.rotate(mv, true);
+            }
+        }
+        Some("show") => (),
+        Some("facelets") => match words.next() {
+            Some(s) => *cube = fresh(cube, Cube::from_facelets(s).map_err(|e| e.to_string())?),
+            None => {
+                println!("{}", cube.to_facelets());
+                return Ok(true);
+            }
+        },
+        Some("history") => {
+            println!("{}{}", "MOVES: ".bright_green(), cube.movs);
+            if !cube.undone.is_empty() {
+                println!(
+                    "{}{}",
+                    "UNDONE: ".bright_green(),
+                    cube.undone.iter().rev().cloned().collect::<Algorithm>()
+                );
+            }
+            return Ok(true);
+        }
+        Some("help") => {
+            println!("{}", HELP);
+            return Ok(true);
+        }
+        Some("quit") | Some("exit") => return Ok(false),
+        Some(_) => {
+            for mv in
+                Cube::alg_parser(line).map_err(|e| format!("{}\n\n{}", e, e.underline(line)))?
+            {
+                cube.rotate(mv, true);
+            }
+        }
+        None => return Ok(true),
+    }
+    println!("\n{}", cube_2_str(cube, facelets));
+    Ok(true)
+}
+
+pub fn repl(mut cube: Cube, facelets: bool) {
+    let mut editor = Editor::<()>::new();
+
+    println!("\n{}", cube_2_str(&cube, facelets));
+    println!("Type \"help\" for the list of commands.");
+    loop {
+        match editor.readline("rubik> ") {
+            Ok(line) => {
+                editor.add_history_entry(line.as_str());
+                match exec(&mut cube, line.trim(), facelets) {
+                    Ok(true) => (),
+                    Ok(false) => break,
+                    Err(err) => eprintln!("{} {}", "error:".bright_red(), err),
+                }
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("{} {}", "error:".bright_red(), err);
+                break;
+            }
+        }
+    }
+}
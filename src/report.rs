@@ -0,0 +1,60 @@
+use super::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseReport {
+    pub phase: usize,
+    pub moves: Algorithm,
+    pub length: usize,
+    pub time_ms: f64,
+    pub facelets: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SolveReport {
+    pub scramble: Algorithm,
+    pub initial: String,
+    pub orientation: Algorithm,
+    pub phases: Vec<PhaseReport>,
+    pub solution: Algorithm,
+    pub length: usize,
+    pub time_ms: f64,
+    #[serde(rename = "final")]
+    pub final_state: String,
+}
+
+impl SolveReport {
+    pub fn new(cube: &Cube, sol: &Solution) -> Self {
+        let mut state = cube.clone();
+
+        for mv in &sol.orientation {
+            state.rotate(*mv, false);
+        }
+        Self {
+            scramble: cube.movs.clone(),
+            initial: cube.to_facelets(),
+            orientation: sol.orientation.clone(),
+            phases: sol
+                .phases
+                .iter()
+                .enumerate()
+                .map(|(i, phase)| {
+                    for mv in &phase.movs {
+                        state.rotate(*mv, false);
+                    }
+                    PhaseReport {
+                        phase: i + 1,
+                        moves: phase.movs.clone(),
+                        length: phase.movs.len_in(Htm),
+                        time_ms: phase.time.as_secs_f64() * 1000.,
+                        facelets: state.to_facelets(),
+                    }
+                })
+                .collect(),
+            solution: sol.movs(),
+            length: sol.len(),
+            time_ms: sol.time().as_secs_f64() * 1000.,
+            final_state: state.to_facelets(),
+        }
+    }
+}
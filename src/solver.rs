@@ -0,0 +1,232 @@
+use super::*;
+use crossbeam::thread;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    Cube(CubeError),
+    Table(Id, TableError),
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolveError::Cube(err) => write!(f, "{}", err),
+            SolveError::Table(id, err) => write!(f, "Table {}: {}", id, err),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<CubeError> for SolveError {
+    fn from(err: CubeError) -> Self {
+        SolveError::Cube(err)
+    }
+}
+
+pub struct Phase {
+    pub movs: Algorithm,
+    pub time: Duration,
+}
+
+pub struct Solution {
+    pub orientation: Algorithm,
+    pub phases: Vec<Phase>,
+}
+
+impl Solution {
+    pub fn len(&self) -> usize {
+        self.movs().len_in(Htm)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn time(&self) -> Duration {
+        self.phases.iter().map(|p| p.time).sum()
+    }
+
+    pub fn movs(&self) -> Algorithm {
+        self.phases
+            .iter()
+            .fold(self.orientation.clone(), |acc, p| acc.concat(&p.movs))
+            .simplify()
+    }
+}
+
+type Progress = Box<dyn Fn(Id, usize, usize) + Send + Sync>;
+type Warning = Box<dyn Fn(&SolveError) + Send + Sync>;
+
+pub struct Solver {
+    tables: [OnceLock<HashMap<u64, Vec<u8>>>; 4],
+    generate: AtomicBool,
+    progress: RwLock<Progress>,
+    warning: RwLock<Warning>,
+    dir: RwLock<PathBuf>,
+    fallback_dir: RwLock<Option<PathBuf>>,
+    lock: Mutex<()>,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self {
+            tables: Default::default(),
+            generate: AtomicBool::new(true),
+            progress: RwLock::new(Box::new(|_, _, _| ())),
+            warning: RwLock::new(Box::new(|_| ())),
+            dir: RwLock::new(TableInfos::default_dir()),
+            fallback_dir: RwLock::new(None),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn set_dir(&self, dir: &Path) {
+        *self.dir.write().unwrap() = dir.to_path_buf();
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.dir.read().unwrap().clone()
+    }
+
+    pub fn set_fallback_dir(&self, dir: Option<&Path>) {
+        *self.fallback_dir.write().unwrap() = dir.map(Path::to_path_buf);
+    }
+
+    pub fn set_generate(&self, generate: bool) {
+        self.generate.store(generate, Ordering::Relaxed);
+    }
+
+    pub fn set_progress(&self, progress: impl Fn(Id, usize, usize) + Send + Sync + 'static) {
+        *self.progress.write().unwrap() = Box::new(progress);
+    }
+
+    pub fn set_warning(&self, warning: impl Fn(&SolveError) + Send + Sync + 'static) {
+        *self.warning.write().unwrap() = Box::new(warning);
+    }
+
+    pub fn global() -> &'static Solver {
+        static SOLVER: OnceLock<Solver> = OnceLock::new();
+
+        SOLVER.get_or_init(Solver::new)
+    }
+
+    fn table(&self, tab_inf: &TableInfos) -> Result<&HashMap<u64, Vec<u8>>, SolveError> {
+        let cell = &self.tables[tab_inf.id - 1];
+
+        if let Some(table) = cell.get() {
+            return Ok(table);
+        }
+
+        let _guard = self.lock.lock().unwrap();
+
+        if let Some(table) = cell.get() {
+            return Ok(table);
+        }
+
+        let mut table: HashMap<u64, Vec<u8>> = HashMap::with_capacity(tab_inf.cap);
+
+        #[cfg(feature = "embedded-tables")]
+        table
+            .load_bytes(tab_inf.embedded(), tab_inf)
+            .map_err(|err| SolveError::Table(tab_inf.id, err))?;
+        #[cfg(not(feature = "embedded-tables"))]
+        self.load(&mut table, tab_inf)?;
+        Ok(cell.get_or_init(|| table))
+    }
+
+    #[cfg(not(feature = "embedded-tables"))]
+    fn load(
+        &self,
+        table: &mut HashMap<u64, Vec<u8>>,
+        tab_inf: &TableInfos,
+    ) -> Result<(), SolveError> {
+        let file = tab_inf.path(&self.dir.read().unwrap());
+        let fallback = self
+            .fallback_dir
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|dir| tab_inf.path(dir));
+        let res = match (table.load(&file, tab_inf), fallback) {
+            (Err(MissingFile(_)), Some(fallback)) if fallback.exists() => {
+                table.load(&fallback, tab_inf)
+            }
+            (res, _) => res,
+        };
+
+        match res {
+            Err(MissingFile(_)) if self.generate.load(Ordering::Relaxed) => {
+                *table = Extractor::table_extract(tab_inf, &**self.progress.read().unwrap())
+                    .map_err(|err| SolveError::Table(tab_inf.id, err))?;
+                if let Err(err) = table.save(&file, tab_inf) {
+                    (self.warning.read().unwrap())(&SolveError::Table(tab_inf.id, err));
+                }
+                Ok(())
+            }
+            res => res.map_err(|err| SolveError::Table(tab_inf.id, err)),
+        }
+    }
+
+    pub fn solve(&self, cube: &mut Cube) -> Result<Solution, SolveError> {
+        cube.validate()?;
+
+        let orientation = cube.orientation().ok_or(MisplacedCenters)?;
+        let mut phases = Vec::with_capacity(Extractor::TAB_INF.len());
+
+        for mv in &orientation {
+            cube.rotate(*mv, true);
+        }
+        for tab_inf in &Extractor::TAB_INF {
+            let table = self.table(tab_inf)?;
+            let start = Instant::now();
+
+            phases.push(Phase {
+                movs: table.exec((tab_inf.key_gen)(cube), cube),
+                time: start.elapsed(),
+            });
+        }
+        Ok(Solution {
+            orientation,
+            phases,
+        })
+    }
+
+    pub fn solve_batch(&self, cubes: &[Cube]) -> Vec<Result<Solution, SolveError>> {
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_sz = cubes.len().div_ceil(workers).max(1);
+
+        thread::scope(|s| {
+            let thrds: Vec<_> = cubes
+                .chunks(chunk_sz)
+                .map(|cubs| {
+                    s.spawn(move |_| {
+                        cubs.iter()
+                            .map(|cub| self.solve(&mut cub.clone()))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            thrds
+                .into_iter()
+                .flat_map(|thrd| thrd.join().unwrap())
+                .collect()
+        })
+        .unwrap()
+    }
+}
+
+pub fn solve(cube: &mut Cube) -> Result<Solution, SolveError> {
+    Solver::global().solve(cube)
+}
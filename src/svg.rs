@@ -0,0 +1,112 @@
+use super::*;
+
+impl MyColor {
+    pub fn hex(&self) -> &'static str {
+        match self {
+            Green => "#00a651",
+            Blue => "#0051ba",
+            Orange => "#ff8000",
+            Yellow => "#ffd500",
+            Red => "#c41e3a",
+            White => "#ffffff",
+            Void => "#202020",
+        }
+    }
+}
+
+impl Cube {
+    const SVG_STICKER: f64 = 30.;
+
+    fn svg_sticker(&self, pos: Id, face: Face) -> &'static str {
+        self.scheme.color(self.sub_2_col(self.ids[pos], face)).hex()
+    }
+
+    fn svg_doc(width: f64, height: f64, body: &str) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+            viewBox=\"0 0 {w} {h}\">\n{}</svg>\n",
+            body,
+            w = width,
+            h = height
+        )
+    }
+
+    pub fn to_svg(&self) -> String {
+        let sz = Self::SVG_STICKER;
+        let mut body = String::new();
+
+        for (face, (x, y)) in [
+            (Up, (3., 0.)),
+            (Left, (0., 3.)),
+            (Front, (3., 3.)),
+            (Right, (6., 3.)),
+            (Back, (9., 3.)),
+            (Down, (3., 6.)),
+        ] {
+            for (i, pos) in Self::facelet_pos(face).into_iter().enumerate() {
+                body.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" \
+                    stroke=\"#000\" stroke-width=\"2\"/>\n",
+                    (x + (i % 3) as f64) * sz + 2.,
+                    (y + (i / 3) as f64) * sz + 2.,
+                    sz,
+                    sz,
+                    self.svg_sticker(pos, face)
+                ));
+            }
+        }
+        Self::svg_doc(12. * sz + 4., 9. * sz + 4., &body)
+    }
+
+    pub fn to_svg_iso(&self) -> String {
+        let sz = Self::SVG_STICKER;
+        let (cos, sin) = (30_f64.to_radians().cos(), 30_f64.to_radians().sin());
+        let proj = |x: f64, y: f64, z: f64| {
+            (
+                3. * sz * cos + 2. + (x - y) * cos * sz,
+                3. * sz + 2. + (x + y) * sin * sz - z * sz,
+            )
+        };
+        let mut body = String::new();
+
+        for face in [Up, Front, Right] {
+            for (i, pos) in Self::facelet_pos(face).into_iter().enumerate() {
+                let (r, c) = ((i / 3) as f64, (i % 3) as f64);
+                let quad = match face {
+                    Up => [
+                        (c, r, 3.),
+                        (c + 1., r, 3.),
+                        (c + 1., r + 1., 3.),
+                        (c, r + 1., 3.),
+                    ],
+                    Front => [
+                        (c, 3., 3. - r),
+                        (c + 1., 3., 3. - r),
+                        (c + 1., 3., 2. - r),
+                        (c, 3., 2. - r),
+                    ],
+                    _ => [
+                        (3., 3. - c, 3. - r),
+                        (3., 2. - c, 3. - r),
+                        (3., 2. - c, 2. - r),
+                        (3., 3. - c, 2. - r),
+                    ],
+                };
+
+                body.push_str(&format!(
+                    "<polygon points=\"{}\" fill=\"{}\" stroke=\"#000\" stroke-width=\"2\"/>\n",
+                    quad.iter()
+                        .map(|(x, y, z)| {
+                            let (px, py) = proj(*x, *y, *z);
+
+                            format!("{:.1},{:.1}", px, py)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    self.svg_sticker(pos, face)
+                ));
+            }
+        }
+        Self::svg_doc(6. * sz * cos + 4., 6. * sz + 4., &body)
+    }
+}
@@ -1,5 +1,42 @@
 use super::*;
 use compressor::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    MissingFile(String),
+    Io(String, String),
+    TruncatedRecord,
+    BadHeader(String),
+    Overflow,
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MissingFile(file) => write!(f, "File \"{}\" not found", file),
+            Io(file, err) => write!(f, "Cannot access \"{}\": {}", file, err),
+            TruncatedRecord => write!(f, "Table data is truncated"),
+            BadHeader(err) => write!(f, "Bad table header: {}", err),
+            Overflow => write!(f, "Value does not fit in its bit field"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl TableError {
+    fn from_io(file: &Path, err: std::io::Error) -> Self {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            MissingFile(file.display().to_string())
+        } else {
+            Io(file.display().to_string(), err.to_string())
+        }
+    }
+}
 
 pub struct TableInfos {
     pub id: Id,
@@ -10,14 +47,116 @@ pub struct TableInfos {
     pub cap: usize,
 }
 
+impl TableInfos {
+    pub const DIR_VAR: &'static str = "RUBIK_TABLES_DIR";
+
+    pub fn default_dir() -> PathBuf {
+        if let Some(dir) = env::var_os(Self::DIR_VAR).filter(|dir| !dir.is_empty()) {
+            return dir.into();
+        }
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .map_or_else(
+                || PathBuf::from("tabs"),
+                |cache| cache.join("rusted_rubiks"),
+            )
+    }
+
+    pub fn path(&self, dir: &Path) -> PathBuf {
+        dir.join(format!("mt_table_{}", self.id))
+    }
+
+    #[cfg(feature = "embedded-tables")]
+    pub fn embedded(&self) -> &'static [u8] {
+        const TABLES: [&[u8]; 4] = [
+            include_bytes!("../tabs/mt_table_1"),
+            include_bytes!("../tabs/mt_table_2"),
+            include_bytes!("../tabs/mt_table_3"),
+            include_bytes!("../tabs/mt_table_4"),
+        ];
+
+        TABLES[self.id - 1]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableHeader {
+    pub magic: [u8; 4],
+    pub version: u16,
+    pub id: Id,
+    pub key_sz: usize,
+    pub set_sz: usize,
+    pub rank: usize,
+    pub count: usize,
+    pub checksum: u64,
+}
+
+impl TableHeader {
+    pub const MAGIC: [u8; 4] = *b"RRTB";
+    pub const VERSION: u16 = 1;
+
+    pub fn new(inf: &TableInfos, count: usize, body: &[u8]) -> Self {
+        Self {
+            magic: Self::MAGIC,
+            version: Self::VERSION,
+            id: inf.id,
+            key_sz: inf.key_sz,
+            set_sz: inf.set_sz,
+            rank: inf.rank,
+            count,
+            checksum: Self::checksum(body),
+        }
+    }
+
+    // 64bit FNV-1a
+    pub fn checksum(body: &[u8]) -> u64 {
+        body.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    pub fn check(&self, inf: &TableInfos, body: &[u8]) -> Result<(), TableError> {
+        let expected = Self::new(inf, self.count, body);
+
+        if self.magic != Self::MAGIC {
+            Err(BadHeader("not a table file".to_string()))
+        } else if self.version != Self::VERSION {
+            Err(BadHeader(format!(
+                "format version {} (expected {})",
+                self.version,
+                Self::VERSION
+            )))
+        } else if self.checksum != expected.checksum {
+            Err(BadHeader("checksum mismatch".to_string()))
+        } else if *self != expected {
+            Err(BadHeader(format!(
+                "generated for table {} with key size {}, set size {} and rank {} \
+                (expected table {} with key size {}, set size {} and rank {})",
+                self.id,
+                self.key_sz,
+                self.set_sz,
+                self.rank,
+                expected.id,
+                expected.key_sz,
+                expected.set_sz,
+                expected.rank
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 pub trait Table {
     fn ins_min(&mut self, key: u64, movs: Vec<u8>);
-    fn save(&self, file: &str, key_sz: usize);
-    fn load(&mut self, file: &str, key_sz: usize);
-    fn exec(&self, key: u64, cube: &mut Cube) -> String;
+    fn save(&self, file: &Path, inf: &TableInfos) -> Result<(), TableError>;
+    fn load(&mut self, file: &Path, inf: &TableInfos) -> Result<(), TableError>;
+    fn load_bytes(&mut self, bin: &[u8], inf: &TableInfos) -> Result<(), TableError>;
+    fn exec(&self, key: u64, cube: &mut Cube) -> Algorithm;
     fn u8_2_mov(mov: u8) -> Move {
         Move(
-            Face::FACE_SET[(mov >> 2) as usize],
+            Outer(Face::FACE_SET[(mov >> 2) as usize]),
             Rotation::ROT_SET[((mov >> 1) & 0b1) as usize],
             RotType::TYPE_SET[(mov & 0b1) as usize],
         )
@@ -38,39 +177,158 @@ impl Table for HashMap<u64, Vec<u8>> {
         }
     }
 
-    fn save(&self, file: &str, key_sz: usize) {
+    fn save(&self, file: &Path, inf: &TableInfos) -> Result<(), TableError> {
         let mut compressor = Compressor::new();
 
         for (k, v) in self {
-            compressor.push(*k, key_sz);
-            compressor.push(v.len() as u8, 4);
+            compressor.push(*k, inf.key_sz)?;
+            compressor.push(v.len() as u8, 4)?;
             for m in v {
-                compressor.push(*m, 5);
+                compressor.push(*m, 5)?;
             }
         }
-        compressor.save(file);
+
+        let body = compressor.bytes();
+        let mut bin = bincode::serialize(&TableHeader::new(inf, self.len(), &body)).unwrap();
+
+        bin.extend(body);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir).map_err(|e| Io(file.display().to_string(), e.to_string()))?;
+        }
+        fs::write(file, bin).map_err(|e| Io(file.display().to_string(), e.to_string()))
     }
 
-    fn load(&mut self, file: &str, key_sz: usize) {
-        let mut decompressor = Decompressor::new(file);
+    fn load(&mut self, file: &Path, inf: &TableInfos) -> Result<(), TableError> {
+        self.load_bytes(
+            &fs::read(file).map_err(|e| TableError::from_io(file, e))?,
+            inf,
+        )
+    }
+
+    fn load_bytes(&mut self, bin: &[u8], inf: &TableInfos) -> Result<(), TableError> {
+        let header: TableHeader =
+            bincode::deserialize(bin).map_err(|_| BadHeader("not a table file".to_string()))?;
+        let body = &bin[bincode::serialized_size(&header).unwrap() as usize..];
+
+        header.check(inf, body)?;
 
-        while let Some(key) = decompressor.pop(key_sz) {
-            self.insert(
-                key,
-                (0..decompressor.pop(4).unwrap())
-                    .map(|_| decompressor.pop(5).unwrap())
-                    .collect::<Vec<u8>>(),
-            );
+        let mut decompressor = Decompressor::from_bytes(body);
+        let mut count = 0;
+
+        while let Some(key) = decompressor.pop(inf.key_sz)? {
+            let len: u8 = decompressor.pop(4)?.ok_or(TruncatedRecord)?;
+            let mut movs = Vec::with_capacity(len as usize);
+
+            for _ in 0..len {
+                movs.push(decompressor.pop(5)?.ok_or(TruncatedRecord)?);
+            }
+            self.insert(key, movs);
+            count += 1;
         }
+        if count != header.count {
+            return Err(BadHeader(format!(
+                "{} entries read (expected {})",
+                count, header.count
+            )));
+        }
+        Ok(())
     }
 
-    fn exec(&self, key: u64, cube: &mut Cube) -> String {
-        let mut disp_res = String::new();
+    fn exec(&self, key: u64, cube: &mut Cube) -> Algorithm {
+        let movs: Algorithm = self[&key].iter().map(|mv| Self::u8_2_mov(*mv)).collect();
 
-        for mv in self[&key].iter().map(|mv| Self::u8_2_mov(*mv)) {
-            std::fmt::write(&mut disp_res, format_args!("{} ", mv)).unwrap();
-            cube.rotate(mv, true);
+        for mv in &movs {
+            cube.rotate(*mv, true);
         }
-        disp_res
+        movs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED: &[u8] = include_bytes!("../tabs/mt_table_1");
+
+    fn inf(id: Id) -> &'static TableInfos {
+        &Extractor::TAB_INF[id - 1]
+    }
+
+    fn saved(table: &HashMap<u64, Vec<u8>>, inf: &TableInfos) -> Vec<u8> {
+        let file = env::temp_dir().join(format!("rubik_2_table_{}", std::process::id()));
+
+        table.save(&file, inf).unwrap();
+
+        let bin = fs::read(&file).unwrap();
+
+        fs::remove_file(&file).unwrap();
+        bin
+    }
+
+    fn load(bin: &[u8], id: Id) -> Result<HashMap<u64, Vec<u8>>, TableError> {
+        let mut table = HashMap::new();
+
+        table.load_bytes(bin, inf(id)).map(|_| table)
+    }
+
+    fn header_len(bin: &[u8]) -> usize {
+        let header: TableHeader = bincode::deserialize(bin).unwrap();
+
+        bincode::serialized_size(&header).unwrap() as usize
+    }
+
+    #[test]
+    fn round_trip() {
+        let table = load(SHIPPED, 1).unwrap();
+
+        assert_eq!(table.len(), inf(1).cap);
+        assert_eq!(load(&saved(&table, inf(1)), 1).unwrap(), table);
+    }
+
+    #[test]
+    fn wrong_table() {
+        assert!(matches!(
+            load(SHIPPED, 2),
+            Err(BadHeader(err)) if err.starts_with("generated for table 1")
+        ));
+    }
+
+    #[test]
+    fn flipped_byte() {
+        let mut bin = SHIPPED.to_vec();
+        let last = bin.len() - 1;
+
+        bin[last] ^= 0b100;
+        assert_eq!(
+            load(&bin, 1),
+            Err(BadHeader("checksum mismatch".to_string()))
+        );
+    }
+
+    #[test]
+    fn truncated_body() {
+        assert_eq!(
+            load(&SHIPPED[..SHIPPED.len() - 10], 1),
+            Err(BadHeader("checksum mismatch".to_string()))
+        );
+    }
+
+    #[test]
+    fn headerless_file() {
+        assert_eq!(
+            load(&SHIPPED[header_len(SHIPPED)..], 1),
+            Err(BadHeader("not a table file".to_string()))
+        );
+    }
+
+    #[test]
+    fn truncated_record() {
+        let table = HashMap::from([(5, vec![1, 2])]);
+        let bin = saved(&table, inf(1));
+        let body = &bin[header_len(&bin) + 1..];
+        let mut bin = bincode::serialize(&TableHeader::new(inf(1), 1, body)).unwrap();
+
+        bin.extend(body);
+        assert_eq!(load(&bin, 1), Err(TruncatedRecord));
     }
 }
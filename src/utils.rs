@@ -2,7 +2,7 @@ use super::*;
 
 pub type Id = usize;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MyColor {
     Green,
     Blue,
@@ -15,24 +15,145 @@ pub enum MyColor {
 
 impl MyColor {
     pub const COL_SET: [MyColor; 6] = [Blue, Green, White, Yellow, Orange, Red];
+
+    pub fn letter(&self) -> char {
+        match self {
+            Green => 'G',
+            Blue => 'B',
+            Orange => 'O',
+            Yellow => 'Y',
+            Red => 'R',
+            White => 'W',
+            Void => '.',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<MyColor> {
+        [Green, Blue, Orange, Yellow, Red, White]
+            .iter()
+            .find(|c| c.letter() == letter)
+            .cloned()
+    }
+
+    pub fn render(&self, palette: Palette) -> String {
+        self.render_marked(palette, false)
+    }
+
+    pub fn render_marked(&self, palette: Palette, marked: bool) -> String {
+        let mark = if marked { '*' } else { ' ' };
+
+        if !colored::control::SHOULD_COLORIZE.should_colorize() {
+            return format!("{}{}", self.letter(), mark);
+        }
+        match palette {
+            Standard => {
+                let text = if marked { "<>".black() } else { "  ".normal() };
+
+                match self {
+                    Green => text.on_bright_green(),
+                    Blue => text.on_bright_blue(),
+                    Orange => text.on_truecolor(255, 128, 0),
+                    Yellow => text.on_bright_yellow(),
+                    Red => text.on_bright_red(),
+                    White => text.on_bright_white(),
+                    Void => text.on_black(),
+                }
+            }
+            ColorBlind => {
+                let (r, g, b) = match self {
+                    Green => (0, 158, 115),
+                    Blue => (0, 114, 178),
+                    Orange => (230, 159, 0),
+                    Yellow => (240, 228, 66),
+                    Red => (213, 94, 0),
+                    White => (255, 255, 255),
+                    Void => (0, 0, 0),
+                };
+
+                format!("{}{}", self.letter(), mark)
+                    .black()
+                    .on_truecolor(r, g, b)
+            }
+        }
+        .to_string()
+    }
 }
 
 impl std::fmt::Display for MyColor {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Green => "  ".on_bright_green(),
-            Blue => "  ".on_bright_blue(),
-            Orange => "  ".on_yellow(),
-            Yellow => "  ".on_bright_yellow(),
-            Red => "  ".on_bright_red(),
-            White => "  ".on_bright_white(),
-            Void => "  ".on_black(),
+        self.render(Standard).fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Palette {
+    #[default]
+    Standard,
+    ColorBlind,
+}
+
+impl std::str::FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "standard" => Ok(Standard),
+            "colorblind" => Ok(ColorBlind),
+            _ => Err(format!("Palette \"{}\" is not recognized", s)),
         }
-        .fmt(f)
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scheme(pub [MyColor; 6]);
+
+impl Scheme {
+    pub const CLASSIC: Scheme = Scheme(MyColor::COL_SET);
+    pub const WESTERN: Scheme = Scheme([White, Yellow, Green, Blue, Orange, Red]);
+    pub const JAPANESE: Scheme = Scheme([White, Blue, Green, Yellow, Orange, Red]);
+
+    pub fn color(&self, col: MyColor) -> MyColor {
+        match MyColor::COL_SET.iter().position(|c| *c == col) {
+            Some(i) => self.0[i],
+            None => col,
+        }
+    }
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+impl std::str::FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "classic" => return Ok(Self::CLASSIC),
+            "western" => return Ok(Self::WESTERN),
+            "japanese" => return Ok(Self::JAPANESE),
+            _ => (),
+        }
+
+        let cols: Vec<MyColor> = s.chars().filter_map(MyColor::from_letter).collect();
+        let mut result = Self::CLASSIC;
+
+        if s.chars().count() != 6 || cols.len() != 6 {
+            return Err(format!("Scheme \"{}\" is not recognized", s));
+        }
+        for (face, col) in Cube::FACELET_ORDER.iter().zip(&cols) {
+            if cols.iter().filter(|c| *c == col).count() > 1 {
+                return Err(format!("Color \"{}\" is used twice", col.letter()));
+            }
+            result.0[*face as usize] = *col;
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Face {
     Up,
     Down,
@@ -44,6 +165,33 @@ pub enum Face {
 
 impl Face {
     pub const FACE_SET: [Face; 6] = [Up, Down, Front, Back, Left, Right];
+
+    pub fn opposite(&self) -> Face {
+        Self::FACE_SET[*self as usize ^ 1]
+    }
+
+    pub fn axis(&self) -> Axis {
+        match self {
+            Left | Right => X,
+            Up | Down => Y,
+            Front | Back => Z,
+        }
+    }
+
+    pub fn slice(&self) -> Slice {
+        match self {
+            Left | Right => Middle,
+            Up | Down => Equator,
+            Front | Back => Standing,
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Face> {
+        Self::FACE_SET
+            .iter()
+            .find(|f| f.to_string().starts_with(letter))
+            .cloned()
+    }
 }
 
 impl std::fmt::Display for Face {
@@ -60,7 +208,118 @@ impl std::fmt::Display for Face {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slice {
+    Middle,
+    Equator,
+    Standing,
+}
+
+impl Slice {
+    pub const SLICE_SET: [Slice; 3] = [Middle, Equator, Standing];
+
+    pub fn face(&self) -> Face {
+        match self {
+            Middle => Left,
+            Equator => Down,
+            Standing => Front,
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Slice> {
+        Self::SLICE_SET
+            .iter()
+            .find(|s| s.to_string().starts_with(letter))
+            .cloned()
+    }
+}
+
+impl std::fmt::Display for Slice {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Middle => "M",
+            Equator => "E",
+            Standing => "S",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const AXIS_SET: [Axis; 3] = [X, Y, Z];
+
+    pub fn face(&self) -> Face {
+        match self {
+            X => Right,
+            Y => Up,
+            Z => Front,
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Axis> {
+        Self::AXIS_SET
+            .iter()
+            .find(|a| a.to_string().starts_with(letter))
+            .cloned()
+    }
+}
+
+impl std::fmt::Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            X => "x",
+            Y => "y",
+            Z => "z",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layer {
+    Outer(Face),
+    Inner(Slice),
+    Wide(Face),
+    Whole(Axis),
+}
+
+impl Layer {
+    pub fn axis(&self) -> Axis {
+        match self {
+            Outer(face) | Wide(face) => face.axis(),
+            Inner(slice) => slice.face().axis(),
+            Whole(axis) => *axis,
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Layer> {
+        Face::from_letter(letter)
+            .map(Outer)
+            .or_else(|| Slice::from_letter(letter).map(Inner))
+            .or_else(|| Axis::from_letter(letter).map(Whole))
+            .or_else(|| Face::from_letter(letter.to_ascii_uppercase()).map(Wide))
+    }
+}
+
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Outer(face) => face.fmt(f),
+            Inner(slice) => slice.fmt(f),
+            Wide(face) => write!(f, "{}w", face),
+            Whole(axis) => axis.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Rotation {
     Cw,
     Ccw,
@@ -79,7 +338,7 @@ impl std::fmt::Display for Rotation {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RotType {
     Single,
     Dual,
@@ -89,17 +348,52 @@ impl RotType {
     pub const TYPE_SET: [RotType; 2] = [Single, Dual];
 }
 
-#[derive(Clone, Copy)]
-pub struct Move(pub Face, pub Rotation, pub RotType);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move(pub Layer, pub Rotation, pub RotType);
+
+impl Move {
+    pub fn quarters(&self) -> usize {
+        match self {
+            Move(_, _, Dual) => 2,
+            Move(_, Cw, Single) => 1,
+            Move(_, Ccw, Single) => 3,
+        }
+    }
+
+    pub fn from_quarters(layer: Layer, quarters: usize) -> Option<Move> {
+        match quarters % 4 {
+            1 => Some(Move(layer, Cw, Single)),
+            2 => Some(Move(layer, Cw, Dual)),
+            3 => Some(Move(layer, Ccw, Single)),
+            _ => None,
+        }
+    }
+
+    pub fn notation(&self) -> String {
+        let Move(layer, rot, typ) = self;
+
+        if *typ == Dual {
+            format!("{}2", layer)
+        } else {
+            format!("{}{}", layer, rot)
+        }
+    }
+
+    pub fn inv(&self) -> Move {
+        let Move(layer, rot, typ) = *self;
+
+        Move(layer, if let Cw = rot { Ccw } else { Cw }, typ)
+    }
+}
 
 impl std::fmt::Display for Move {
     fn fmt(&self, fm: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let Move(face, rot, typ) = self;
+        let Move(layer, rot, typ) = self;
 
         write!(
             fm,
             "{}{}",
-            face.to_string().bright_yellow(),
+            layer.to_string().bright_yellow(),
             if *typ == Dual {
                 "2".bright_red()
             } else {